@@ -0,0 +1,436 @@
+use std::{
+    io::{self, Cursor},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use image::{DynamicImage, ImageError, ImageResult};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    runtime::Handle,
+    sync::Semaphore,
+};
+
+use crate::{
+    config::{EncoderConfig, ImageFormat},
+    Decoder, Encoder,
+};
+
+/// Blocking thread pool running the CPU heavy decoding and encoding work of [`AsyncDecoder`]
+/// and [`AsyncEncoder`], so it doesn't stall the async executor.
+///
+/// Jobs run on the blocking pool of a tokio runtime, by default the one the future is polled
+/// on. A dedicated runtime keeps image processing from competing with other blocking work of
+/// the service, and the number of concurrent jobs can be limited. Jobs waiting for a free slot
+/// are cancelled when their future is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::BlockingPool;
+///
+/// let runtime = tokio::runtime::Builder::new_multi_thread()
+///     .max_blocking_threads(4)
+///     .build()?;
+///
+/// let pool = BlockingPool::new(runtime.handle().clone()).with_max_jobs(4);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BlockingPool {
+    /// Runtime the jobs are spawned on, the current one if not set.
+    handle: Option<Handle>,
+
+    /// Limit of concurrently running jobs.
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl BlockingPool {
+    /// Creates a new [`BlockingPool`] spawning jobs on the specified runtime.
+    ///
+    /// # Parameters
+    ///
+    /// - `handle`: Handle of the tokio runtime which blocking pool runs the jobs.
+    #[inline]
+    pub fn new(handle: Handle) -> Self {
+        Self {
+            handle: Some(handle),
+            permits: None,
+        }
+    }
+
+    /// Limits the number of jobs running at the same time, other jobs wait for a free slot.
+    ///
+    /// # Parameters
+    ///
+    /// - `jobs`: The maximum number of concurrent jobs, at least 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::BlockingPool;
+    ///
+    /// let pool = BlockingPool::default().with_max_jobs(2);
+    /// ```
+    #[inline]
+    pub fn with_max_jobs(mut self, jobs: usize) -> Self {
+        self.permits = Some(Arc::new(Semaphore::new(jobs.max(1))));
+        self
+    }
+
+    /// Runs the job on the blocking pool.
+    ///
+    /// If the returned future is dropped, jobs that haven't started yet are skipped. Jobs
+    /// that already run can't be interrupted and their result is discarded.
+    pub(crate) async fn run<T, F>(&self, job: F) -> ImageResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> ImageResult<T> + Send + 'static,
+    {
+        let permit = match &self.permits {
+            Some(permits) => Some(
+                Arc::clone(permits)
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _guard = CancelOnDrop(Arc::clone(&cancelled));
+
+        let task = move || {
+            // keeps the slot taken until the job finishes, even if the future is gone
+            let _permit = permit;
+
+            if cancelled.load(Ordering::Acquire) {
+                return Err(ImageError::IoError(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    "Job was cancelled",
+                )));
+            }
+
+            job()
+        };
+
+        let handle = match &self.handle {
+            Some(handle) => handle.spawn_blocking(task),
+            None => tokio::task::spawn_blocking(task),
+        };
+
+        match handle.await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(ImageError::IoError(io::Error::other(e))),
+        }
+    }
+}
+
+/// Flags the job as cancelled when the future awaiting it is dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// Decoder reading images from an [`AsyncRead`] source.
+///
+/// The input is read into memory without blocking, then decoded by [`Decoder`] on a
+/// [`BlockingPool`]. Dropping the future, for example when a request is aborted, stops reading
+/// and cancels decoding that didn't start yet.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::AsyncDecoder;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+/// # runtime.block_on(async {
+/// let file = tokio::fs::File::open("tests/files/png/f1t.png").await?;
+///
+/// let image = AsyncDecoder::new(file).decode().await?;
+///
+/// assert_eq!(image.width(), 48);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// # })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct AsyncDecoder<R: AsyncRead + Unpin> {
+    r: R,
+    format: Option<ImageFormat>,
+    page: Option<usize>,
+    pool: BlockingPool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncDecoder<R> {
+    /// Creates a new [`AsyncDecoder`] with the specified input reader.
+    ///
+    /// The image format is detected from the content unless set with
+    /// [`AsyncDecoder::with_format`].
+    ///
+    /// # Parameters
+    ///
+    /// - `r`: The input reader implementing [`AsyncRead`].
+    #[inline]
+    pub fn new(r: R) -> Self {
+        Self {
+            r,
+            format: None,
+            page: None,
+            pool: BlockingPool::default(),
+        }
+    }
+
+    /// Sets the image format for the decoder, see [`Decoder::with_format`].
+    #[inline]
+    pub fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Selects the page of a multi-page image to decode, see [`Decoder::with_page`].
+    #[inline]
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Sets the [`BlockingPool`] decoding runs on, the current runtime by default.
+    #[inline]
+    pub fn with_pool(mut self, pool: BlockingPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Decodes the image, see [`Decoder::decode`].
+    pub async fn decode(self) -> ImageResult<DynamicImage> {
+        let (decoder, pool) = self.read().await?;
+
+        pool.run(move || decoder.decode()).await
+    }
+
+    /// Decodes every page of a multi-page image, see [`Decoder::decode_pages`].
+    pub async fn decode_pages(self) -> ImageResult<Vec<DynamicImage>> {
+        let (decoder, pool) = self.read().await?;
+
+        pool.run(move || decoder.decode_pages()).await
+    }
+
+    /// Reads the whole input and prepares the blocking decoder for it.
+    async fn read(mut self) -> ImageResult<(Decoder<Cursor<Vec<u8>>>, BlockingPool)> {
+        let mut buf = vec![];
+        self.r.read_to_end(&mut buf).await?;
+
+        let mut decoder = Decoder::new(Cursor::new(buf));
+
+        if let Some(format) = self.format {
+            decoder = decoder.with_format(format);
+        }
+
+        if let Some(page) = self.page {
+            decoder = decoder.with_page(page);
+        }
+
+        Ok((decoder, self.pool))
+    }
+}
+
+/// Encoder writing images to an [`AsyncWrite`] destination.
+///
+/// The image is encoded by [`Encoder`] into memory on a [`BlockingPool`], then written
+/// without blocking. Dropping the future, for example when a request is aborted, cancels
+/// encoding that didn't start yet and stops writing.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::{config::{Codec, EncoderConfig}, AsyncEncoder};
+/// use image::{DynamicImage, RgbaImage};
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+/// # runtime.block_on(async {
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(100, 50));
+///
+/// let mut output = vec![];
+///
+/// AsyncEncoder::new(&mut output, image)
+///     .with_config(EncoderConfig::new(Codec::Png))
+///     .encode()
+///     .await?;
+///
+/// assert!(output.starts_with(b"\x89PNG"));
+/// # Ok::<(), image::ImageError>(())
+/// # })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct AsyncEncoder<W: AsyncWrite + Unpin> {
+    w: W,
+    data: DynamicImage,
+    conf: EncoderConfig,
+    pool: BlockingPool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncEncoder<W> {
+    /// Creates a new [`AsyncEncoder`] with the specified writer and image data.
+    ///
+    /// # Parameters
+    ///
+    /// - `w`: The output writer implementing [`AsyncWrite`].
+    /// - `data`: The image data to be encoded.
+    #[inline]
+    pub fn new(w: W, data: DynamicImage) -> Self {
+        Self {
+            w,
+            data,
+            conf: EncoderConfig::default(),
+            pool: BlockingPool::default(),
+        }
+    }
+
+    /// Sets the configuration for the encoder, see [`Encoder::with_config`].
+    #[inline]
+    pub fn with_config(mut self, config: EncoderConfig) -> Self {
+        self.conf = config;
+        self
+    }
+
+    /// Sets the [`BlockingPool`] encoding runs on, the current runtime by default.
+    #[inline]
+    pub fn with_pool(mut self, pool: BlockingPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Encodes the image and writes it to the writer, see [`Encoder::encode`].
+    pub async fn encode(mut self) -> ImageResult<()> {
+        let (data, conf) = (self.data, self.conf);
+
+        let buf = self
+            .pool
+            .run(move || {
+                let mut buf = vec![];
+                Encoder::new(&mut buf, data).with_config(conf).encode()?;
+
+                Ok(buf)
+            })
+            .await?;
+
+        self.w.write_all(&buf).await?;
+        self.w.flush().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use image::RgbaImage;
+    use tokio::runtime::{Builder, Runtime};
+
+    use super::*;
+    use crate::config::Codec;
+
+    fn runtime() -> Runtime {
+        Builder::new_current_thread().enable_time().build().unwrap()
+    }
+
+    #[test]
+    fn decode_and_encode() {
+        runtime().block_on(async {
+            let image = DynamicImage::ImageRgba8(RgbaImage::new(16, 8));
+            let mut buf = vec![];
+
+            AsyncEncoder::new(&mut buf, image)
+                .with_config(EncoderConfig::new(Codec::Png))
+                .encode()
+                .await
+                .unwrap();
+
+            let decoded = AsyncDecoder::new(&buf[..]).decode().await.unwrap();
+
+            assert_eq!((decoded.width(), decoded.height()), (16, 8));
+        });
+    }
+
+    #[test]
+    fn decode_on_dedicated_runtime() {
+        let pool_runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .max_blocking_threads(1)
+            .build()
+            .unwrap();
+
+        let pool = BlockingPool::new(pool_runtime.handle().clone()).with_max_jobs(1);
+
+        runtime().block_on(async {
+            let file = tokio::fs::File::open("tests/files/png/f1t.png").await;
+            let file = file.unwrap();
+
+            let pages = AsyncDecoder::new(file)
+                .with_pool(pool)
+                .decode_pages()
+                .await
+                .unwrap();
+
+            assert_eq!(pages.len(), 1);
+        });
+    }
+
+    #[test]
+    fn decode_error() {
+        runtime().block_on(async {
+            let result = AsyncDecoder::new(&b"not an image"[..]).decode().await;
+
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn cancel_queued_job() {
+        runtime().block_on(async {
+            let pool = BlockingPool::default().with_max_jobs(1);
+            let (tx, rx) = std::sync::mpsc::channel::<()>();
+
+            let running = tokio::spawn({
+                let pool = pool.clone();
+                async move {
+                    pool.run(move || {
+                        rx.recv().unwrap();
+                        Ok(())
+                    })
+                    .await
+                }
+            });
+
+            tokio::time::sleep(Duration::from_millis(50)).await;
+
+            let started = Arc::new(AtomicBool::new(false));
+
+            let queued = pool.run({
+                let started = Arc::clone(&started);
+                move || {
+                    started.store(true, Ordering::Release);
+                    Ok(())
+                }
+            });
+
+            // the request is aborted while the job waits for a free slot
+            let aborted = tokio::time::timeout(Duration::from_millis(50), queued).await;
+            assert!(aborted.is_err());
+
+            tx.send(()).unwrap();
+            running.await.unwrap().unwrap();
+
+            pool.run(|| Ok(())).await.unwrap();
+
+            assert!(!started.load(Ordering::Acquire));
+        });
+    }
+}
@@ -0,0 +1,118 @@
+//! Crash-safe writing of output files.
+
+use std::{
+    collections::BTreeSet,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// Temporary files of writes in progress, removed when the run is interrupted.
+static PENDING: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the file through a temporary file in the same directory, which is renamed over the
+/// target once complete, so the target is never left truncated.
+pub fn write(path: &Path, data: &[u8]) -> io::Result<()> {
+    write_with(path, data, |_| Ok(()))
+}
+
+/// Writes the file like [`write`], calling `finish` with the temporary file before it's renamed.
+pub fn write_with(
+    path: &Path,
+    data: &[u8],
+    finish: impl FnOnce(&Path) -> io::Result<()>,
+) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    fs::create_dir_all(dir)?;
+
+    let temp = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    PENDING.lock().unwrap().insert(temp.clone());
+
+    let result = write_temp(&temp, data)
+        .and_then(|()| finish(&temp))
+        .and_then(|()| {
+            // renamed under the lock, so an interrupted run can't leave it behind
+            let mut pending = PENDING.lock().unwrap();
+            pending.remove(&temp);
+
+            fs::rename(&temp, path)
+        });
+
+    if result.is_err() {
+        PENDING.lock().unwrap().remove(&temp);
+        let _ = fs::remove_file(&temp);
+    }
+
+    result
+}
+
+fn write_temp(temp: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(temp)?;
+
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+/// Removes temporary files of writes in progress, which blocks writes from completing.
+///
+/// Called when the run is interrupted, before the process exits.
+pub fn cleanup() {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+
+    for temp in pending.iter() {
+        let _ = fs::remove_file(temp);
+    }
+
+    // keep the lock, so no temporary file is renamed over its target afterwards
+    std::mem::forget(pending);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image.png");
+
+        write(&path, b"first").unwrap();
+        write(&path, b"second").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn remove_temp_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // renaming a file over a non-empty directory fails
+        let path = dir.path().join("image.png");
+        fs::create_dir_all(path.join("dir")).unwrap();
+
+        assert!(write(&path, b"data").is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        // other tests may be writing at the same time
+        let pending = PENDING.lock().unwrap();
+        assert!(!pending.iter().any(|temp| temp.starts_with(dir.path())));
+    }
+}
@@ -0,0 +1,213 @@
+//! Preservation of file attributes on outputs.
+
+use std::{
+    fs::{self, Metadata},
+    io,
+    path::Path,
+};
+
+#[cfg(unix)]
+use std::ffi::OsString;
+
+use filetime::FileTime;
+
+/// File attributes copied from inputs to outputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Preserve {
+    /// Access and modification times.
+    pub timestamps: bool,
+
+    /// Permission bits.
+    pub mode: bool,
+
+    /// Owner and group, where permitted.
+    pub ownership: bool,
+
+    /// Extended attributes, where permitted.
+    pub xattrs: bool,
+}
+
+impl Preserve {
+    /// Every attribute.
+    pub const ALL: Self = Self {
+        timestamps: true,
+        mode: true,
+        ownership: true,
+        xattrs: true,
+    };
+
+    /// Attribute names accepted by [`Preserve::from_names`].
+    pub const NAMES: [&'static str; 5] = ["timestamps", "mode", "ownership", "xattr", "all"];
+
+    /// Builds the set of attributes from their names, unknown names are ignored.
+    pub fn from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        names
+            .into_iter()
+            .fold(Self::default(), |preserve, name| match name {
+                "timestamps" => Self {
+                    timestamps: true,
+                    ..preserve
+                },
+                "mode" => Self {
+                    mode: true,
+                    ..preserve
+                },
+                "ownership" => Self {
+                    ownership: true,
+                    ..preserve
+                },
+                "xattr" => Self {
+                    xattrs: true,
+                    ..preserve
+                },
+                "all" => Self::ALL,
+                _ => preserve,
+            })
+    }
+
+    /// Checks whether no attribute is preserved.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Attributes of an input file, read before the input may be replaced by its output.
+#[derive(Debug)]
+pub struct Attributes {
+    preserve: Preserve,
+    metadata: Metadata,
+
+    #[cfg(unix)]
+    xattrs: Vec<(OsString, Vec<u8>)>,
+}
+
+/// Checks whether the error means the attribute can't be set by this user or on this file system.
+fn is_not_permitted(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::PermissionDenied | io::ErrorKind::Unsupported
+    )
+}
+
+impl Attributes {
+    /// Reads attributes of the file.
+    pub fn read(path: &Path, preserve: Preserve) -> io::Result<Self> {
+        #[cfg(unix)]
+        let xattrs = match preserve.xattrs {
+            true => match xattr::list(path) {
+                Ok(names) => names
+                    .filter_map(|name| {
+                        let value = xattr::get(path, &name).ok()??;
+                        Some((name, value))
+                    })
+                    .collect(),
+                Err(e) if is_not_permitted(&e) => vec![],
+                Err(e) => return Err(e),
+            },
+            false => vec![],
+        };
+
+        Ok(Self {
+            preserve,
+            metadata: fs::metadata(path)?,
+            #[cfg(unix)]
+            xattrs,
+        })
+    }
+
+    /// Sets attributes of the file, skipping ownership and extended attributes that aren't
+    /// permitted.
+    pub fn apply(&self, path: &Path) -> io::Result<()> {
+        // before the mode, which may make the file read-only
+        #[cfg(unix)]
+        for (name, value) in &self.xattrs {
+            match xattr::set(path, name, value) {
+                Err(e) if !is_not_permitted(&e) => return Err(e),
+                _ => {}
+            }
+        }
+
+        // before the mode, as changing the owner clears setuid and setgid bits
+        #[cfg(unix)]
+        if self.preserve.ownership {
+            use std::os::unix::fs::{chown, MetadataExt};
+
+            let (uid, gid) = (self.metadata.uid(), self.metadata.gid());
+
+            // users can only change the group of their files
+            match chown(path, Some(uid), Some(gid)) {
+                Err(e) if is_not_permitted(&e) => match chown(path, None, Some(gid)) {
+                    Err(e) if !is_not_permitted(&e) => return Err(e),
+                    _ => {}
+                },
+                result => result?,
+            }
+        }
+
+        if self.preserve.mode {
+            fs::set_permissions(path, self.metadata.permissions())?;
+        }
+
+        if self.preserve.timestamps {
+            filetime::set_file_times(
+                path,
+                FileTime::from_last_access_time(&self.metadata),
+                FileTime::from_last_modification_time(&self.metadata),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preserve_names() {
+        assert!(Preserve::from_names([]).is_empty());
+        assert_eq!(Preserve::from_names(["all"]), Preserve::ALL);
+        assert_eq!(
+            Preserve::from_names(["timestamps", "mode"]),
+            Preserve {
+                timestamps: true,
+                mode: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn copy_attributes() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let (input, output) = (dir.path().join("a.png"), dir.path().join("a.jpg"));
+        fs::write(&input, "image").unwrap();
+        fs::write(&output, "optimized").unwrap();
+
+        let mtime = FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&input, mtime).unwrap();
+
+        let mut permissions = fs::metadata(&input).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&input, permissions).unwrap();
+
+        Attributes::read(&input, Preserve::ALL)
+            .unwrap()
+            .apply(&output)
+            .unwrap();
+
+        let metadata = fs::metadata(&output).unwrap();
+        assert_eq!(FileTime::from_last_modification_time(&metadata), mtime);
+        assert!(metadata.permissions().readonly());
+
+        // read-only files can't be removed on windows
+        for path in [&input, &output] {
+            let mut permissions = fs::metadata(path).unwrap().permissions();
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            fs::set_permissions(path, permissions).unwrap();
+        }
+    }
+}
@@ -105,7 +105,10 @@ impl ExifTool {
         &mut self,
         iterator: impl IntoIterator<Item = (PathBuf, PathBuf)>,
         backup: bool,
-        ) -> Result<(), Box<dyn Error>> {
+        preserve_timestamps: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        // keep modification time of outputs
+        let preserve = if preserve_timestamps { "-P\n" } else { "" };
 
         // Loop over target files
         iterator.into_iter()
@@ -115,7 +118,7 @@ impl ExifTool {
                 }
 
                 let cmd = format!(
-                    "-overwrite_original_in_place\n-tagsFromFile\n{}\n{}\n-execute\n",
+                    "-overwrite_original_in_place\n{preserve}-tagsFromFile\n{}\n{}\n-execute\n",
                     input.as_os_str().to_str().unwrap(),
                     output.as_os_str().to_str().unwrap()
                 );
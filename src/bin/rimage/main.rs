@@ -1,14 +1,34 @@
-use std::{error::Error, path::PathBuf, str::FromStr};
 use clap::{arg, value_parser, ArgAction, Command};
+use globset::Glob;
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
-#[cfg(all(feature = "exiftool", feature = "parallel"))]
-use rayon::{iter::IntoParallelIterator, iter::ParallelIterator};
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
 
-use paths::collect_files;
-use rimage::config::{Codec, EncoderConfig, QuantizationConfig, ResizeConfig, ResizeType};
+use attributes::Preserve;
+use manifest::Manifest;
+use paths::{collect_files, glob_set, Collision, Placeholder, Template, Values, WalkOptions};
+use progress::Progress;
+use rimage::config::{
+    Background, Codec, Dithering, EncoderConfig, MinSavings, Palette, QuantizationConfig,
+    ResizeConfig, ResizeType, TiffCompression, ToneMapping, ToneMappingConfig,
+};
 
+mod atomic;
+mod attributes;
+mod manifest;
+mod operations;
 mod optimize;
 mod paths;
+mod progress;
+mod quantization;
+mod report;
+mod watch;
 
 #[cfg(feature = "exiftool")]
 mod exiftool;
@@ -21,7 +41,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .author("Vladyslav Vladinov <vladinov.dev@gmail.com>")
         .about("A tool to convert/optimize/resize images in different formats")
         .arg(
-            arg!(<FILES> "Input file(s) to process")
+            arg!(<FILES> "Input file(s) to process, \"-\" reads from stdin")
                 .num_args(1..)
                 .value_delimiter(None)
                 .value_parser(value_parser!(PathBuf)),
@@ -31,28 +51,105 @@ fn main() -> Result<(), Box<dyn Error>> {
             arg!(-q --quality <QUALITY> "Optimization image quality, disabled when use Jpegxl format\n[range: 1 - 100]")
                 .value_parser(value_parser!(f32))
                 .default_value("75"),
-            arg!(-f --codec <CODEC> "Image codec to use\n[possible values: png, oxipng, jpegxl, webp, avif]")
+            arg!(-f --codec <CODEC> "Image codec to use\n[possible values: png, oxipng, jpegxl, webp, avif, tiff, gif, qoi, bmp]")
                 .value_parser(Codec::from_str)
                 .default_value("mozjpeg"),
-            arg!(-o --output <DIR> "Write output file(s) to <DIR>, if \"-r\" option is not used")
+            arg!(-o --output <DIR> "Write output file(s) to <DIR>, if \"-r\" option is not used, \"-\" writes to stdout")
                 .value_parser(value_parser!(PathBuf)),
-            arg!(-r --recursive "Saves output file(s) preserving folder structure")
+            arg!(-r --recursive "Processes directories recursively and saves output file(s) preserving folder structure")
                 .action(ArgAction::SetTrue),
             arg!(-s --suffix [SUFFIX] "Appends suffix to output file(s) names"),
+            arg!(--name <TEMPLATE> "Template of output file(s) names, codec extension is appended\n[placeholders: {stem}, {ext}, {width}, {height}, {codec}, {quality}, {hash8}, {dir}] [default: {stem}]")
+                .value_parser(Template::from_str)
+                .conflicts_with("suffix"),
+            arg!(--"on-collision" <POLICY> "What to do when multiple inputs would be written to the same output\n[possible values: error, rename, overwrite] [default: error]")
+                .value_parser(Collision::from_str),
             arg!(-b --backup "Appends \".backup\" suffix to input file(s) extension")
                 .action(ArgAction::SetTrue),
+            arg!(--preserve [ATTRS] "Copy attributes of input file(s) to outputs, ownership and extended attributes where permitted\n[possible values: timestamps, mode, ownership, xattr, all] [default: all]")
+                .value_parser(Preserve::NAMES)
+                .value_delimiter(',')
+                .require_equals(true)
+                .default_missing_value("all")
+                .hide_possible_values(true),
+            arg!(--"no-progress" "Don't show progress bar")
+                .action(ArgAction::SetTrue),
+            arg!(--"dry-run" "Print input and output file(s) paths without processing anything")
+                .action(ArgAction::SetTrue),
+            arg!(--report <FORMAT> "Print report of processed file(s) with sizes, dimensions, settings and timing\n[possible values: json]")
+                .value_parser(["json"])
+                .hide_possible_values(true),
+            arg!(--manifest <FILE> "Skip input file(s) unchanged since the run recorded in manifest file, with the same options")
+                .value_parser(value_parser!(PathBuf))
+                .conflicts_with("backup"),
+            arg!(--prune "Remove output file(s) of inputs that no longer exist, recorded in manifest file")
+                .action(ArgAction::SetTrue)
+                .requires("manifest"),
+            arg!(--watch "Keep running and optimize images created or modified in input file(s) and directories")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["dry-run", "report", "prune"]),
+            arg!(--debounce <MS> "Time in milliseconds writes to a watched file must settle before it's optimized\n[default: 500]")
+                .value_parser(value_parser!(u64))
+                .requires("watch"),
+            arg!(--"min-savings" <SIZE> "Keep the original image when output isn't smaller by at least this number of bytes or percentage\n[examples: 0, 1024, 10%]")
+                .value_parser(MinSavings::from_str),
+            arg!(--"tiff-compression" <METHOD> "Compression used by tiff codec\n[possible values: none, lzw, deflate] [default: lzw]")
+                .value_parser(TiffCompression::from_str),
+            arg!(--background <COLOR> "Background for transparent images when codec doesn't support alpha\n[examples: white, #ff8800, checkerboard, checkerboard:16] [default: white]")
+                .value_parser(Background::from_str),
+            arg!(--page <INDEX> "Page of multi-page TIFF or sub-image of ICO to process, starting from 0")
+                .value_parser(value_parser!(usize)),
+            arg!(--"all-pages" "Processes every page of multi-page images into outputs named with page index\n[example: scan.tiff -> scan-0.png, scan-1.png]")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("page"),
+            #[cfg(feature = "svg")]
+            arg!(--dpi <DPI> "Resolution used to render vector images without target size\n[default: 96]")
+                .value_parser(value_parser!(f32)),
             #[cfg(feature = "parallel")]
             arg!(-t --threads <NUM> "Number of threads to use\n[range: 1 - 16] [default: number of cores]")
                 .value_parser(value_parser!(usize)),
         ])
+        .next_help_heading("Input files")
+        .args([
+            arg!(--include <GLOB> "Only process files matching pattern, can be used multiple times\n[example: \"**/*.png\"]")
+                .value_parser(Glob::new)
+                .action(ArgAction::Append),
+            arg!(--exclude <GLOB> "Skip files and directories matching pattern, can be used multiple times\n[example: \"**/thumbnails\"]")
+                .value_parser(Glob::new)
+                .action(ArgAction::Append),
+            arg!(--extensions <EXT> "Extensions of files collected from directories, separated by commas\n[default: all supported image formats]")
+                .value_delimiter(','),
+            arg!(--"max-depth" <DEPTH> "Maximum depth of subdirectories to process with \"-r\"")
+                .value_parser(value_parser!(usize))
+                .requires("recursive"),
+            arg!(--"follow-symlinks" "Follow symbolic links found in directories")
+                .action(ArgAction::SetTrue),
+            arg!(--hidden "Process hidden files and directories")
+                .action(ArgAction::SetTrue),
+        ])
         .next_help_heading("Quantization")
         .args([
-            arg!(--quantization [QUALITY] "Enables quantization with optional quality\n[range: 1 - 100] [default: 75]")
-                .value_parser(value_parser!(u8).range(..=100))
+            arg!(--quantization [QUALITY] "Enables quantization with optional quality or min-max quality range\nImages that can't reach minimum quality are skipped\n[range: 1 - 100] [default: 75]")
+                .value_parser(quantization::parse_quality_range)
                 .default_missing_value("75"),
             arg!(--dithering [QUALITY] "Enables dithering with optional quality\n[range: 1 - 100] [default: 75]")
                 .value_parser(value_parser!(f32))
-                .default_missing_value("75")
+                .default_missing_value("75"),
+            arg!(--colors <NUM> "Maximum number of colors in palette\n[range: 2 - 256] [default: 256]")
+                .value_parser(value_parser!(u32).range(2..=256)),
+            arg!(--speed <SPEED> "Quantization speed, 1 is slowest with best quality\n[range: 1 - 10] [default: 4]")
+                .value_parser(value_parser!(u8).range(1..=10)),
+            arg!(--posterize <BITS> "Number of least significant bits to ignore\n[range: 0 - 4] [default: 0]")
+                .value_parser(value_parser!(u8).range(..=4)),
+            arg!(--"input-gamma" <GAMMA> "Gamma of input image, 0 means sRGB\n[range: 0 - 1] [default: 0]")
+                .value_parser(value_parser!(f64)),
+            arg!(--"shared-palette" "Quantize all input files with one shared palette")
+                .action(ArgAction::SetTrue),
+            arg!(--palette <FILE> "Remap images to fixed palette from GIMP palette or hex colors file\n[formats: .gpl, .hex]")
+                .value_parser(quantization::parse_palette)
+                .conflicts_with("shared-palette"),
+            arg!(--"dithering-method" <METHOD> "Dithering method used for quantization\n[possible values: floyd-steinberg, atkinson, ordered, blue-noise] [default: floyd-steinberg]")
+                .value_parser(Dithering::from_str),
         ])
         .next_help_heading("Resizing")
         .args([
@@ -64,6 +161,18 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .value_parser(ResizeType::from_str)
                 .default_value("lanczos3")
         ])
+        .next_help_heading("Tone mapping")
+        .args([
+            arg!(--"tone-mapping" <OPERATOR> "Operator mapping HDR images for codecs that can't store them\n[possible values: clip, reinhard, aces, hable, bt2390] [default: aces]")
+                .value_parser(ToneMapping::from_str),
+            arg!(--exposure <EV> "Exposure adjustment of HDR images in stops\n[range: -20 - 20] [default: 0]")
+                .value_parser(value_parser!(f32))
+                .allow_negative_numbers(true),
+            arg!(--"peak-luminance" <NITS> "Peak luminance of HDR images in nits\n[range: 203 - 10000] [default: 1000]")
+                .value_parser(value_parser!(f32)),
+        ])
+        .next_help_heading("Operations (applied in the order given, can be repeated)")
+        .args(operations::args())
         .get_matches();
 
     let codec = matches.get_one::<Codec>("codec").unwrap();
@@ -79,14 +188,34 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut quantization_config = QuantizationConfig::new();
 
-    if let Some(quality) = matches.get_one::<u8>("quantization") {
-        quantization_config = quantization_config.with_quality(*quality)?
+    if let Some((min, max)) = matches.get_one::<(u8, u8)>("quantization") {
+        quantization_config = quantization_config.with_quality_range(*min, *max)?
     }
 
     if let Some(dithering) = matches.get_one::<f32>("dithering") {
         quantization_config = quantization_config.with_dithering(*dithering / 100.0)?
     }
 
+    if let Some(method) = matches.get_one::<Dithering>("dithering-method") {
+        quantization_config = quantization_config.with_dithering_method(*method)
+    }
+
+    if let Some(colors) = matches.get_one::<u32>("colors") {
+        quantization_config = quantization_config.with_max_colors(*colors)?
+    }
+
+    if let Some(speed) = matches.get_one::<u8>("speed") {
+        quantization_config = quantization_config.with_speed(*speed)?
+    }
+
+    if let Some(bits) = matches.get_one::<u8>("posterize") {
+        quantization_config = quantization_config.with_posterization(*bits)?
+    }
+
+    if let Some(gamma) = matches.get_one::<f64>("input-gamma") {
+        quantization_config = quantization_config.with_gamma(*gamma)?
+    }
+
     let resize_filter = matches.get_one::<ResizeType>("filter").unwrap();
 
     let mut resize_config = ResizeConfig::new(*resize_filter);
@@ -99,68 +228,352 @@ fn main() -> Result<(), Box<dyn Error>> {
         resize_config = resize_config.with_height(*height);
     }
 
-    let mut conf = EncoderConfig::new(*codec).with_quality(*quality)?;
+    let mut tone_mapping = ToneMappingConfig::new(
+        matches
+            .get_one::<ToneMapping>("tone-mapping")
+            .copied()
+            .unwrap_or_default(),
+    );
+
+    if let Some(exposure) = matches.get_one::<f32>("exposure") {
+        tone_mapping = tone_mapping.with_exposure(*exposure)?;
+    }
+
+    if let Some(peak_luminance) = matches.get_one::<f32>("peak-luminance") {
+        tone_mapping = tone_mapping.with_peak_luminance(*peak_luminance)?;
+    }
+
+    let mut conf = EncoderConfig::new(*codec)
+        .with_quality(*quality)?
+        .with_tone_mapping(tone_mapping);
 
-    if matches.get_one::<u8>("quantization").is_some()
-        || matches.get_one::<f32>("dithering").is_some()
+    if let Some(compression) = matches.get_one::<TiffCompression>("tiff-compression") {
+        conf = conf.with_tiff_compression(*compression);
+    }
+
+    if let Some(background) = matches.get_one::<Background>("background") {
+        conf = conf.with_background(*background);
+    }
+
+    if [
+        "quantization",
+        "dithering",
+        "dithering-method",
+        "colors",
+        "speed",
+        "posterize",
+        "input-gamma",
+    ]
+    .into_iter()
+    .any(|id| matches.contains_id(id))
     {
         conf = conf.with_quantization(quantization_config);
     }
 
+    if let Some(palette) = matches.get_one::<Palette>("palette") {
+        conf = conf.with_palette(palette.clone());
+    }
+
     if matches.get_one::<usize>("width").is_some() || matches.get_one::<usize>("height").is_some() {
         conf = conf.with_resize(resize_config);
     }
 
-    let files = matches
+    let operations = operations::collect_operations(&matches);
+
+    let files: Vec<PathBuf> = matches
         .get_many::<PathBuf>("FILES")
         .unwrap_or_default()
         .map(|v| v.into())
         .collect();
 
-    // start exiftool (do this before so the slow startup time can happen parallel to encoding)
-    #[cfg(feature = "exiftool")]
-    let mut exiftool = ExifTool::new()?;
-
     let out_dir = matches.get_one::<PathBuf>("output").map(|p| p.into());
     let suffix = matches.get_one::<String>("suffix").map(|p| p.into());
     let recursive = matches.get_one::<bool>("recursive").unwrap_or(&false);
     let backup = matches.get_one::<bool>("backup").unwrap_or(&false);
-    let filelist = collect_files(files);
 
-    optimize::optimize_files(
-        paths::get_paths(
-            filelist.clone(),
+    let walk_options = WalkOptions {
+        include: matches
+            .get_many::<Glob>("include")
+            .map(|patterns| glob_set(patterns.cloned()))
+            .transpose()?,
+        exclude: matches
+            .get_many::<Glob>("exclude")
+            .map(|patterns| glob_set(patterns.cloned()))
+            .transpose()?,
+        extensions: matches
+            .get_many::<String>("extensions")
+            .map(|extensions| extensions.cloned().collect()),
+        recursive: *recursive,
+        max_depth: matches.get_one::<usize>("max-depth").copied(),
+        follow_symlinks: matches.get_flag("follow-symlinks"),
+        hidden: matches.get_flag("hidden"),
+    };
+
+    let watch = matches.get_flag("watch");
+    let inputs = files.clone();
+
+    // outputs are placed relative to the inputs, so files found later by watch mode are written
+    // next to the outputs of files found on start
+    let common_path = match *recursive {
+        true => paths::get_input_root(&inputs),
+        false => None,
+    };
+
+    let filelist = collect_files(files, &walk_options);
+
+    let pages = match matches.get_one::<usize>("page") {
+        Some(page) => optimize::Pages::Index(*page),
+        None if matches.get_flag("all-pages") => optimize::Pages::All,
+        None => optimize::Pages::Default,
+    };
+
+    let stdout = filelist.iter().any(|path| paths::is_stdio(path))
+        || out_dir.as_deref().is_some_and(paths::is_stdio);
+
+    if stdout && filelist.len() > 1 {
+        return Err("only a single image can be read from stdin or written to stdout".into());
+    }
+
+    if stdout && watch {
+        return Err("\"--watch\" can't be used with stdin or stdout".into());
+    }
+
+    // stdin has no name or content to read ahead, and stdout has no file name
+    if stdout && matches.contains_id("name") {
+        return Err("\"--name\" can't be used with stdin or stdout".into());
+    }
+
+    if stdout && pages == optimize::Pages::All {
+        return Err("\"--all-pages\" can't be used when writing to stdout".into());
+    }
+
+    #[cfg(feature = "svg")]
+    let dpi = matches.get_one::<f32>("dpi").copied();
+    #[cfg(not(feature = "svg"))]
+    let dpi = None;
+
+    let report = matches.get_one::<String>("report").is_some();
+
+    if stdout && report && !matches.get_flag("dry-run") {
+        return Err("\"--report\" can't be used when writing to stdout".into());
+    }
+
+    let template = matches.get_one::<Template>("name");
+    let collision = matches
+        .get_one::<Collision>("on-collision")
+        .copied()
+        .unwrap_or_default();
+
+    // taken before the shared palette is added, which doesn't change dimensions
+    let naming_conf = conf.clone();
+    let codec_name = format!("{codec:?}").to_lowercase();
+
+    let min_savings = matches.get_one::<MinSavings>("min-savings").copied();
+    let written = |input: &Path, output: &Path| {
+        optimize::written_paths(input, output, pages, min_savings)
+    };
+
+    let output_paths = |files: Vec<PathBuf>| -> Result<Vec<(PathBuf, PathBuf)>, String> {
+        let path_vector: Vec<(PathBuf, PathBuf)> = paths::get_paths(
+            files,
             out_dir.clone(),
             suffix.clone(),
             codec.to_extension(),
-            *recursive,
+            common_path.clone(),
+        )
+        .collect();
+
+        let Some(template) = template else {
+            return paths::resolve_collisions(path_vector, collision, written);
+        };
+
+        // inputs that can't be decoded or read are reported and skipped
+        let rename = |(input, output): (PathBuf, PathBuf)| {
+            let mut values = Values::new(&input, &codec_name, naming_conf.quality());
+
+            if template.contains(Placeholder::Width) || template.contains(Placeholder::Height) {
+                let dimensions =
+                    optimize::output_dimensions(&input, &naming_conf, &operations, dpi, pages);
+                values.dimensions = Some(dimensions.map_err(|e| eprintln!("{input:?}: {e}")).ok()?);
+            }
+
+            if template.contains(Placeholder::Hash8) {
+                let hash = manifest::hash_file(&input);
+                values.hash = Some(hash.map_err(|e| eprintln!("{input:?}: {e}")).ok()?);
+            }
+
+            let name = format!("{}.{}", template.render(&values), codec.to_extension());
+
+            Some((input, output.with_file_name(name)))
+        };
+
+        // dimensions are found by decoding the inputs, which is done in parallel like optimizing
+        #[cfg(feature = "parallel")]
+        let path_vector = rayon::iter::IntoParallelIterator::into_par_iter(path_vector)
+            .filter_map(rename)
+            .collect();
+        #[cfg(not(feature = "parallel"))]
+        let path_vector = path_vector.into_iter().filter_map(rename).collect();
+
+        paths::resolve_collisions(path_vector, collision, written)
+    };
+
+    if matches.get_flag("dry-run") {
+        let planned: Vec<_> = output_paths(filelist)?
+            .into_iter()
+            .map(|(input, output)| report::PlannedFile { input, output })
+            .collect();
+
+        if report {
+            println!("{:#}", serde_json::json!({ "files": planned }));
+        } else {
+            for file in planned {
+                println!("{:?} -> {:?}", file.input, file.output);
+            }
+        }
+
+        return Ok(());
+    }
+
+    // start exiftool (do this before so the slow startup time can happen parallel to encoding)
+    #[cfg(feature = "exiftool")]
+    let mut exiftool = ExifTool::new()?;
+
+    // remove partially written outputs when interrupted
+    ctrlc::set_handler(|| {
+        atomic::cleanup();
+        std::process::exit(130);
+    })?;
+
+    let start = Instant::now();
+
+    if matches.get_flag("shared-palette") {
+        conf = conf.with_palette(optimize::shared_palette(
+            &filelist,
+            &quantization_config,
+            &operations,
+            dpi,
+            pages,
+        )?);
+    }
+
+    let options = optimize::Options {
+        backup: *backup,
+        dpi,
+        pages,
+        min_savings,
+        preserve: Preserve::from_names(
+            matches
+                .get_many::<String>("preserve")
+                .unwrap_or_default()
+                .map(String::as_str),
         ),
-        conf,
-        *backup,
-    );
+    };
+
+    let manifest = matches
+        .get_one::<PathBuf>("manifest")
+        .map(|path| {
+            let fingerprint = manifest::fingerprint(&(&conf, &operations, &options));
+
+            Manifest::load(path.clone(), fingerprint)
+        })
+        .transpose()?;
 
     // issue commands to exiftool to copy metadata
     #[cfg(feature = "exiftool")]
-    {
-        #[cfg(feature = "parallel")]
-        let path_vector: Vec<_> = paths::get_paths(
-            filelist,
-            out_dir,
-            suffix,
-            codec.to_extension(),
-            *recursive,
-        ).into_par_iter().collect();
+    let mut copy_metadata = |path_vector: Vec<(PathBuf, PathBuf)>| {
+        let path_vector = path_vector
+            .into_iter()
+            .filter(|(_, output)| !paths::is_stdio(output))
+            .flat_map(|(input, output)| optimize::output_paths(input, output, pages));
 
-        #[cfg(not(feature = "parallel"))]
-        let path_vector = paths::get_paths(
-            filelist,
-            out_dir,
-            suffix,
-            codec.to_extension(),
-            *recursive,
+        exiftool.copy_metadata(path_vector, *backup, options.preserve.timestamps)
+    };
+
+    let show_progress = !matches.get_flag("no-progress");
+    let progress = Progress::new(filelist.len(), show_progress);
+    let path_vector = output_paths(filelist)?;
+
+    let files = optimize::optimize_files(
+        path_vector.clone(),
+        &conf,
+        &operations,
+        options,
+        manifest.as_ref(),
+        &progress,
+    );
+
+    progress.finish();
+
+    if let Some(manifest) = &manifest {
+        if matches.get_flag("prune") {
+            for output in manifest.prune()? {
+                eprintln!("{output:?}: removed, input no longer exists");
+            }
+        }
+
+        manifest.save()?;
+    }
+
+    if report {
+        println!(
+            "{}",
+            report::json_report(&conf, &operations, &files, start.elapsed())?
         );
+    }
+
+    #[cfg(feature = "exiftool")]
+    copy_metadata(path_vector)?;
+
+    if watch {
+        let written = files
+            .into_iter()
+            .flat_map(|file| file.outputs)
+            .map(|output| output.path)
+            .collect();
+
+        let debounce = Duration::from_millis(*matches.get_one::<u64>("debounce").unwrap_or(&500));
+
+        watch::watch(&inputs, &walk_options, debounce, written, |files| {
+            let path_vector = match output_paths(files) {
+                Ok(path_vector) => path_vector,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return vec![];
+                }
+            };
+            let progress = Progress::new(path_vector.len(), show_progress);
+
+            let files = optimize::optimize_files(
+                path_vector.clone(),
+                &conf,
+                &operations,
+                options,
+                manifest.as_ref(),
+                &progress,
+            );
+
+            progress.finish();
+
+            if let Some(manifest) = &manifest {
+                manifest.save().unwrap_or_else(|e| eprintln!("{e}"));
+            }
+
+            #[cfg(feature = "exiftool")]
+            copy_metadata(path_vector).unwrap_or_else(|e| eprintln!("{e}"));
+
+            files
+                .into_iter()
+                .flat_map(|file| {
+                    let input = file.input;
 
-        exiftool.copy_metadata(path_vector, *backup)?;
+                    file.outputs.into_iter().map(move |output| {
+                        eprintln!("{input:?} -> {:?}", output.path);
+                        output.path
+                    })
+                })
+                .collect()
+        })?;
     }
 
     Ok(())
@@ -0,0 +1,250 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::Debug,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::atomic;
+
+/// Version of the manifest format, manifests of other versions are discarded.
+const VERSION: u32 = 1;
+
+/// Manifest of processed inputs, used to skip inputs unchanged since the previous run.
+#[derive(Debug)]
+pub struct Manifest {
+    path: PathBuf,
+    fingerprint: String,
+    entries: Mutex<BTreeMap<PathBuf, Entry>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestFile {
+    version: u32,
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Entry {
+    /// Hash of the input content after it was processed.
+    hash: String,
+
+    /// Fingerprint of the settings the input was processed with.
+    fingerprint: String,
+
+    /// Output path the input was processed to.
+    output: PathBuf,
+
+    /// Files written for the input.
+    outputs: Vec<PathBuf>,
+}
+
+/// Computes the fingerprint of the settings, which changes with any of them or rimage version.
+pub fn fingerprint(settings: &impl Debug) -> String {
+    let settings = format!("{} {settings:?}", env!("CARGO_PKG_VERSION"));
+
+    blake3::hash(settings.as_bytes()).to_hex().to_string()
+}
+
+/// Hashes the file content.
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(File::open(path)?)?;
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+impl Manifest {
+    /// Loads the manifest, which is empty when the file doesn't exist yet.
+    pub fn load(path: PathBuf, fingerprint: String) -> Result<Self, Box<dyn Error>> {
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => {
+                let manifest: ManifestFile = serde_json::from_str(&content)
+                    .map_err(|e| format!("{path:?}: invalid manifest, {e}"))?;
+
+                match manifest.version {
+                    VERSION => manifest.entries,
+                    _ => BTreeMap::new(),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(format!("{path:?}: {e}").into()),
+        };
+
+        Ok(Self {
+            path,
+            fingerprint,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Checks whether the input was processed to the output with the same settings and neither
+    /// the input changed nor any of the written files disappeared since.
+    pub fn is_fresh(&self, input: &Path, output: &Path) -> bool {
+        let Some(entry) = self.entries.lock().unwrap().get(input).cloned() else {
+            return false;
+        };
+
+        entry.fingerprint == self.fingerprint
+            && entry.output == output
+            && entry.outputs.iter().all(|path| path.exists())
+            && hash_file(input).is_ok_and(|hash| hash == entry.hash)
+    }
+
+    /// Records the files written for the input.
+    ///
+    /// The input is hashed after processing, so inputs optimized in place are fresh on the
+    /// next run.
+    pub fn record(&self, input: &Path, output: &Path, outputs: Vec<PathBuf>) -> io::Result<()> {
+        let entry = Entry {
+            hash: hash_file(input)?,
+            fingerprint: self.fingerprint.clone(),
+            output: output.to_path_buf(),
+            outputs,
+        };
+
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(input.to_path_buf(), entry);
+
+        Ok(())
+    }
+
+    /// Removes files written for inputs that no longer exist and forgets these inputs.
+    ///
+    /// # Returns
+    ///
+    /// Returns the removed files.
+    pub fn prune(&self) -> io::Result<Vec<PathBuf>> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut removed = vec![];
+
+        let missing: Vec<_> = entries
+            .keys()
+            .filter(|input| !input.exists())
+            .cloned()
+            .collect();
+
+        for input in missing {
+            let entry = entries.remove(&input).unwrap();
+
+            for output in entry.outputs {
+                match fs::remove_file(&output) {
+                    Ok(()) => removed.push(output),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Writes the manifest file.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let manifest = ManifestFile {
+            version: VERSION,
+            entries: self.entries.lock().unwrap().clone(),
+        };
+
+        atomic::write(
+            &self.path,
+            serde_json::to_string_pretty(&manifest)?.as_bytes(),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_inputs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (input, output) = (dir.join("a.png"), dir.join("a.jpg"));
+        fs::write(&input, "image").unwrap();
+        fs::write(&output, "optimized").unwrap();
+
+        let path = dir.join("manifest.json");
+        let manifest = Manifest::load(path.clone(), fingerprint(&1)).unwrap();
+        assert!(!manifest.is_fresh(&input, &output));
+
+        manifest
+            .record(&input, &output, vec![output.clone()])
+            .unwrap();
+        manifest.save().unwrap();
+
+        let manifest = Manifest::load(path.clone(), fingerprint(&1)).unwrap();
+        assert!(manifest.is_fresh(&input, &output));
+        assert!(!manifest.is_fresh(&input, &dir.join("a.webp")));
+
+        let manifest = Manifest::load(path.clone(), fingerprint(&2)).unwrap();
+        assert!(!manifest.is_fresh(&input, &output));
+
+        fs::write(&input, "changed").unwrap();
+        let manifest = Manifest::load(path, fingerprint(&1)).unwrap();
+        assert!(!manifest.is_fresh(&input, &output));
+    }
+
+    #[test]
+    fn missing_outputs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (input, output) = (dir.join("a.png"), dir.join("a.jpg"));
+        fs::write(&input, "image").unwrap();
+
+        let manifest = Manifest::load(dir.join("manifest.json"), fingerprint(&1)).unwrap();
+        manifest
+            .record(&input, &output, vec![output.clone()])
+            .unwrap();
+
+        assert!(!manifest.is_fresh(&input, &output));
+    }
+
+    #[test]
+    fn prune_outputs() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let (kept, removed) = (dir.join("a.png"), dir.join("b.png"));
+
+        for path in [&kept, &removed] {
+            fs::write(path, "image").unwrap();
+            fs::write(path.with_extension("jpg"), "optimized").unwrap();
+        }
+
+        let manifest = Manifest::load(dir.join("manifest.json"), fingerprint(&1)).unwrap();
+
+        for path in [&kept, &removed] {
+            let output = path.with_extension("jpg");
+            manifest
+                .record(path, &output, vec![output.clone()])
+                .unwrap();
+        }
+
+        fs::remove_file(&removed).unwrap();
+
+        assert_eq!(manifest.prune().unwrap(), [dir.join("b.jpg")]);
+        assert!(dir.join("a.jpg").exists());
+        assert!(!dir.join("b.jpg").exists());
+        assert!(manifest.is_fresh(&kept, &kept.with_extension("jpg")));
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = dir.join("manifest.json");
+        fs::write(&path, "not json").unwrap();
+
+        assert!(Manifest::load(path, fingerprint(&1)).is_err());
+    }
+}
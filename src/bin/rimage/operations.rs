@@ -0,0 +1,291 @@
+use clap::{arg, value_parser, Arg, ArgAction, ArgMatches};
+use rimage::{rgb::RGBA8, Operation};
+
+pub fn parse_crop(s: &str) -> Result<Operation, String> {
+    match parse_numbers(s)?[..] {
+        [x, y, width, height] => Ok(Operation::Crop {
+            x,
+            y,
+            width,
+            height,
+        }),
+        _ => Err(format!(
+            "{s} is not valid crop area, expected X,Y,WIDTH,HEIGHT"
+        )),
+    }
+}
+
+pub fn parse_pad(s: &str) -> Result<Operation, String> {
+    let (top, right, bottom, left) = match parse_numbers(s)?[..] {
+        [all] => (all, all, all, all),
+        [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+        [top, right, bottom, left] => (top, right, bottom, left),
+        _ => {
+            return Err(format!(
+            "{s} is not valid padding, expected SIZE, VERTICAL,HORIZONTAL or TOP,RIGHT,BOTTOM,LEFT"
+        ))
+        }
+    };
+
+    Ok(Operation::Pad {
+        top,
+        right,
+        bottom,
+        left,
+        color: RGBA8::new(0, 0, 0, 0),
+    })
+}
+
+pub fn parse_flip(s: &str) -> Result<Operation, String> {
+    Ok(match s {
+        "horizontal" | "h" => Operation::FlipHorizontally,
+        "vertical" | "v" => Operation::FlipVertically,
+        direction => return Err(format!("{direction} is not valid flip direction")),
+    })
+}
+
+pub fn parse_angle(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(degrees) if degrees.is_finite() => Ok(degrees),
+        _ => Err(format!("{s} is not valid angle")),
+    }
+}
+
+pub fn parse_contrast(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(value) if (-100.0..=100.0).contains(&value) => Ok(value),
+        _ => Err(format!("{s} is not in -100 - 100 range")),
+    }
+}
+
+fn parse_numbers(s: &str) -> Result<Vec<usize>, String> {
+    s.split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .map_err(|_| format!("{n} is not valid number"))
+        })
+        .collect()
+}
+
+/// Arguments of operations, each can be repeated.
+pub fn args() -> [Arg; 9] {
+    [
+        arg!(--crop <AREA> "Crop image to specified area\n[format: x,y,width,height]")
+            .value_parser(parse_crop)
+            .action(ArgAction::Append),
+        arg!(--trim [TOLERANCE] "Trim uniform borders with optional color tolerance\n[range: 0 - 255] [default: 0]")
+            .value_parser(value_parser!(u8))
+            .require_equals(true)
+            .default_missing_value("0")
+            .action(ArgAction::Append),
+        arg!(--rotate <DEGREES> "Rotate image clockwise by specified angle")
+            .value_parser(parse_angle)
+            .allow_negative_numbers(true)
+            .action(ArgAction::Append),
+        arg!(--flip <DIRECTION> "Flip image in specified direction\n[possible values: horizontal, vertical]")
+            .value_parser(parse_flip)
+            .action(ArgAction::Append),
+        arg!(--pad <SIZE> "Add transparent borders around image\n[format: size, vertical,horizontal or top,right,bottom,left]")
+            .value_parser(parse_pad)
+            .action(ArgAction::Append),
+        arg!(--grayscale "Convert image to grayscale")
+            .action(ArgAction::SetTrue),
+        arg!(--brightness <VALUE> "Adjust image brightness\n[range: -255 - 255]")
+            .value_parser(value_parser!(i16).range(-255..=255))
+            .allow_negative_numbers(true)
+            .action(ArgAction::Append),
+        arg!(--contrast <VALUE> "Adjust image contrast in percent\n[range: -100 - 100]")
+            .value_parser(parse_contrast)
+            .allow_negative_numbers(true)
+            .action(ArgAction::Append),
+        arg!(--gamma <VALUE> "Apply gamma correction\n[positive numbers only]")
+            .value_parser(value_parser!(f32))
+            .action(ArgAction::Append),
+    ]
+}
+
+/// Collects operations from the command line in the order they are given.
+pub fn collect_operations(matches: &ArgMatches) -> Vec<Operation> {
+    let mut operations = vec![];
+
+    collect(matches, "crop", &mut operations, |crop: &Operation| *crop);
+    collect(matches, "trim", &mut operations, |tolerance: &u8| {
+        Operation::Trim(*tolerance)
+    });
+    collect(matches, "rotate", &mut operations, |degrees: &f32| {
+        Operation::Rotate(*degrees)
+    });
+    collect(matches, "flip", &mut operations, |flip: &Operation| *flip);
+    collect(matches, "pad", &mut operations, |pad: &Operation| *pad);
+
+    if matches.get_flag("grayscale") {
+        if let Some(index) = matches.index_of("grayscale") {
+            operations.push((index, Operation::Grayscale));
+        }
+    }
+
+    collect(
+        matches,
+        "brightness",
+        &mut operations,
+        |brightness: &i16| Operation::Brightness(*brightness),
+    );
+    collect(matches, "contrast", &mut operations, |contrast: &f32| {
+        Operation::Contrast(*contrast)
+    });
+    collect(matches, "gamma", &mut operations, |gamma: &f32| {
+        Operation::Gamma(*gamma)
+    });
+
+    operations.sort_by_key(|(index, _)| *index);
+    operations
+        .into_iter()
+        .map(|(_, operation)| operation)
+        .collect()
+}
+
+/// Adds every occurrence of the argument with its position on the command line.
+fn collect<T: Clone + Send + Sync + 'static>(
+    matches: &ArgMatches,
+    id: &str,
+    operations: &mut Vec<(usize, Operation)>,
+    operation: impl Fn(&T) -> Operation,
+) {
+    if let (Some(indices), Some(values)) = (matches.indices_of(id), matches.get_many::<T>(id)) {
+        operations.extend(indices.zip(values.map(operation)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_area() {
+        assert_eq!(
+            parse_crop("1, 2,3,4"),
+            Ok(Operation::Crop {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4
+            })
+        );
+        assert!(parse_crop("1,2,3").is_err());
+        assert!(parse_crop("a,2,3,4").is_err());
+    }
+
+    #[test]
+    fn padding() {
+        let transparent = RGBA8::new(0, 0, 0, 0);
+
+        assert_eq!(
+            parse_pad("5"),
+            Ok(Operation::Pad {
+                top: 5,
+                right: 5,
+                bottom: 5,
+                left: 5,
+                color: transparent
+            })
+        );
+        assert_eq!(
+            parse_pad("5,10"),
+            Ok(Operation::Pad {
+                top: 5,
+                right: 10,
+                bottom: 5,
+                left: 10,
+                color: transparent
+            })
+        );
+        assert_eq!(
+            parse_pad("1,2,3,4"),
+            Ok(Operation::Pad {
+                top: 1,
+                right: 2,
+                bottom: 3,
+                left: 4,
+                color: transparent
+            })
+        );
+        assert!(parse_pad("1,2,3").is_err());
+    }
+
+    #[test]
+    fn flip_direction() {
+        assert_eq!(parse_flip("h"), Ok(Operation::FlipHorizontally));
+        assert_eq!(parse_flip("vertical"), Ok(Operation::FlipVertically));
+        assert!(parse_flip("diagonal").is_err());
+    }
+
+    #[test]
+    fn operations_order() {
+        let matches = clap::Command::new("rimage").args(args()).get_matches_from([
+            "rimage",
+            "--gamma",
+            "2",
+            "--trim",
+            "--rotate",
+            "-90",
+            "--grayscale",
+            "--crop",
+            "0,0,10,10",
+            "--gamma=0.5",
+        ]);
+
+        assert_eq!(
+            collect_operations(&matches),
+            [
+                Operation::Gamma(2.0),
+                Operation::Trim(0),
+                Operation::Rotate(-90.0),
+                Operation::Grayscale,
+                Operation::Crop {
+                    x: 0,
+                    y: 0,
+                    width: 10,
+                    height: 10
+                },
+                Operation::Gamma(0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn trim_before_files() {
+        let matches = clap::Command::new("rimage")
+            .args(args())
+            .arg(arg!([FILES] ... "Input files"))
+            .get_matches_from(["rimage", "--trim", "photo.jpg", "--trim=10", "image.png"]);
+
+        assert_eq!(
+            collect_operations(&matches),
+            [Operation::Trim(0), Operation::Trim(10)]
+        );
+        assert_eq!(
+            matches
+                .get_many::<String>("FILES")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            ["photo.jpg", "image.png"]
+        );
+    }
+
+    #[test]
+    fn contrast_range() {
+        assert_eq!(parse_contrast("-100"), Ok(-100.0));
+        assert_eq!(parse_contrast("50.5"), Ok(50.5));
+        assert!(parse_contrast("-150").is_err());
+        assert!(parse_contrast("NaN").is_err());
+    }
+
+    #[test]
+    fn rotation_angle() {
+        assert_eq!(parse_angle("-45.5"), Ok(-45.5));
+        assert!(parse_angle("NaN").is_err());
+        assert!(parse_angle("inf").is_err());
+        assert!(parse_angle("-infinity").is_err());
+    }
+}
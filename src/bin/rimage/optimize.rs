@@ -1,67 +1,546 @@
 use std::{
     error::Error,
-    fs::{self, File},
+    fs,
+    io::{self, BufRead, Read, Seek, Write},
     path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Instant,
 };
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use rimage::{config::EncoderConfig, Decoder, Encoder};
+use image::{DynamicImage, ImageResult};
+use rimage::{
+    config::{EncoderConfig, MinSavings, Palette, QuantizationConfig},
+    error::OperationError,
+    Decoder, Encoder, Image, Operation,
+};
+
+use crate::{
+    atomic,
+    attributes::{Attributes, Preserve},
+    manifest::Manifest,
+    paths::is_stdio,
+    progress::Progress,
+    report::{FileReport, OutputReport, Status},
+};
+
+/// Pages decoded from multi-page images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pages {
+    /// The first page, or the largest sub-image of icons.
+    #[default]
+    Default,
+
+    /// The page at the specified index.
+    Index(usize),
+
+    /// Every page, each written to a separately named output.
+    All,
+}
+
+/// Options of how input files are read and outputs are written.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+    /// Keeps input files with `.backup` suffix once outputs are written.
+    pub backup: bool,
+
+    /// Resolution used to render vector images without target size.
+    pub dpi: Option<f32>,
+
+    /// Pages decoded from multi-page images.
+    pub pages: Pages,
+
+    /// Keeps the original image when the output isn't smaller by at least this much.
+    pub min_savings: Option<MinSavings>,
+
+    /// Attributes of input files copied to outputs.
+    pub preserve: Preserve,
+}
 
 #[cfg(not(feature = "parallel"))]
 pub fn optimize_files(
     paths: impl IntoIterator<Item = (PathBuf, PathBuf)>,
-    conf: EncoderConfig,
-    backup: bool,
-) {
+    conf: &EncoderConfig,
+    operations: &[Operation],
+    options: Options,
+    manifest: Option<&Manifest>,
+    progress: &Progress,
+) -> Vec<FileReport> {
     paths
         .into_iter()
-        .for_each(move |(input, output): (PathBuf, PathBuf)| {
-            optimize(&input, &output, conf.clone(), backup).unwrap_or_else(|e| {
-                dbg!(&e);
-                eprintln!("{input:?}: {e}");
-            });
-        });
+        .map(move |(input, output): (PathBuf, PathBuf)| {
+            optimize_file(
+                input, &output, conf, operations, &options, manifest, progress,
+            )
+        })
+        .collect()
 }
 
 #[cfg(feature = "parallel")]
 pub fn optimize_files(
     paths: impl IntoParallelIterator<Item = (PathBuf, PathBuf)>,
-    conf: EncoderConfig,
-    backup: bool,
-) {
+    conf: &EncoderConfig,
+    operations: &[Operation],
+    options: Options,
+    manifest: Option<&Manifest>,
+    progress: &Progress,
+) -> Vec<FileReport> {
     paths
         .into_par_iter()
-        .for_each(move |(input, output): (PathBuf, PathBuf)| {
-            optimize(&input, &output, conf.clone(), backup).unwrap_or_else(|e| {
-                eprintln!("{input:?}: {e}");
-            });
-        });
+        .map(move |(input, output): (PathBuf, PathBuf)| {
+            optimize_file(
+                input, &output, conf, operations, &options, manifest, progress,
+            )
+        })
+        .collect()
+}
+
+/// Optimizes the file unless it's unchanged since the run recorded in the manifest.
+fn optimize_file(
+    input: PathBuf,
+    output: &Path,
+    conf: &EncoderConfig,
+    operations: &[Operation],
+    options: &Options,
+    manifest: Option<&Manifest>,
+    progress: &Progress,
+) -> FileReport {
+    progress.start(&input);
+
+    let start = Instant::now();
+    let mut report = FileReport {
+        input,
+        ..Default::default()
+    };
+    let input = report.input.clone();
+
+    // images streamed through stdin or stdout can't be checked for changes
+    let manifest = manifest.filter(|_| !is_stdio(&input) && !is_stdio(output));
+
+    if manifest.is_some_and(|manifest| manifest.is_fresh(&input, output)) {
+        report.status = Status::Unchanged;
+    } else {
+        match optimize(
+            &input,
+            output,
+            conf.clone(),
+            operations,
+            options,
+            &mut report,
+            progress,
+        ) {
+            Ok(()) => {
+                if let Some(manifest) = manifest {
+                    let outputs = report.outputs.iter().map(|o| o.path.clone()).collect();
+
+                    manifest
+                        .record(&input, output, outputs)
+                        .unwrap_or_else(|e| progress.println(format_args!("{input:?}: {e}")));
+                }
+            }
+            Err(e) => {
+                progress.println(format_args!("{input:?}: {e}"));
+
+                report.status = Status::Failed;
+                report.error = Some(e.to_string());
+            }
+        }
+    }
+
+    report.duration = start.elapsed().as_secs_f64();
+    progress.finish_file(&report);
+
+    report
+}
+
+/// Builds one palette from all input files, so every output is remapped onto the same colors.
+pub fn shared_palette(
+    paths: &[PathBuf],
+    quantization_config: &QuantizationConfig,
+    operations: &[Operation],
+    dpi: Option<f32>,
+    pages: Pages,
+) -> Result<Palette, Box<dyn Error>> {
+    let images = paths.iter().flat_map(|path| {
+        let images = if is_stdio(path) {
+            decode_stdin(None, operations, dpi, pages)
+        } else {
+            Decoder::from_path(path)
+                .and_then(|decoder| decode(configure(decoder, None, operations, dpi), pages))
+        };
+
+        images
+            .map_err(|e| eprintln!("{path:?}: {e}"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(move |image| {
+                apply_operations(image, operations)
+                    .map_err(|e| eprintln!("{path:?}: {e}"))
+                    .ok()
+            })
+    });
+
+    Ok(Palette::from_images(images, quantization_config)?)
+}
+
+/// Reads the whole stdin, which is kept so the image can be decoded again after building a
+/// shared palette.
+fn stdin() -> io::Result<&'static [u8]> {
+    static STDIN: OnceLock<Vec<u8>> = OnceLock::new();
+
+    if let Some(buf) = STDIN.get() {
+        return Ok(buf);
+    }
+
+    let mut buf = vec![];
+    io::stdin().lock().read_to_end(&mut buf)?;
+
+    Ok(STDIN.get_or_init(|| buf))
+}
+
+/// Decodes the selected pages of the image read from stdin.
+fn decode_stdin(
+    conf: Option<&EncoderConfig>,
+    operations: &[Operation],
+    dpi: Option<f32>,
+    pages: Pages,
+) -> ImageResult<Vec<DynamicImage>> {
+    let decoder = Decoder::from_bytes(stdin()?);
+
+    decode(configure(decoder, conf, operations, dpi), pages)
+}
+
+/// Configures the decoder to render vector images at the resolution or size they are encoded at.
+#[allow(unused_variables, unused_mut)]
+fn configure<R: BufRead + Seek>(
+    mut decoder: Decoder<R>,
+    conf: Option<&EncoderConfig>,
+    operations: &[Operation],
+    dpi: Option<f32>,
+) -> Decoder<R> {
+    #[cfg(feature = "svg")]
+    if let Some(dpi) = dpi {
+        decoder = decoder.with_dpi(dpi);
+    }
+
+    // operations may change the geometry, so only render at target size without them
+    #[cfg(feature = "svg")]
+    if let Some(resize_config) = conf.and_then(|conf| conf.resize_config()) {
+        if operations.is_empty() {
+            decoder = decoder.with_resize_config(*resize_config);
+        }
+    }
+
+    decoder
+}
+
+/// Decodes the selected pages of the image.
+fn decode<R: BufRead + Seek>(decoder: Decoder<R>, pages: Pages) -> ImageResult<Vec<DynamicImage>> {
+    match pages {
+        Pages::Default => Ok(vec![decoder.decode()?]),
+        Pages::Index(page) => Ok(vec![decoder.with_page(page).decode()?]),
+        Pages::All => decoder.decode_pages(),
+    }
+}
+
+/// Appends the page index to the output file name, `scan.png` becomes `scan-1.png`.
+fn page_path(path: &Path, page: usize) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("-{page}"));
+
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+/// Gets the outputs written for the input, which are separate files for every page when all
+/// pages are processed.
+#[cfg(feature = "exiftool")]
+pub fn output_paths(input: PathBuf, output: PathBuf, pages: Pages) -> Vec<(PathBuf, PathBuf)> {
+    match pages {
+        Pages::All => (0..)
+            .map(|page| page_path(&output, page))
+            .take_while(|path| path.exists())
+            .map(|path| (input.clone(), path))
+            .collect(),
+        _ => vec![(input, output)],
+    }
+}
+
+/// Dimensions of the image once resized by the encoder.
+fn output_size(conf: &EncoderConfig, image: &DynamicImage) -> (u32, u32) {
+    match conf.resize_config() {
+        Some(resize_config) => resize_config.dimensions(image.width(), image.height()),
+        None => (image.width(), image.height()),
+    }
+}
+
+/// Decodes the file to get dimensions of its output, or of the first output when all pages are
+/// processed.
+pub fn output_dimensions(
+    path: &Path,
+    conf: &EncoderConfig,
+    operations: &[Operation],
+    dpi: Option<f32>,
+    pages: Pages,
+) -> Result<(u32, u32), Box<dyn Error>> {
+    let decoder = configure(Decoder::from_path(path)?, Some(conf), operations, dpi);
+    let image = decode(decoder, pages)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{path:?}: no image"))?;
+    let image = apply_operations(image, operations)?;
+
+    Ok(output_size(conf, &image))
+}
+
+fn apply_operations(
+    image: DynamicImage,
+    operations: &[Operation],
+) -> Result<DynamicImage, OperationError> {
+    if operations.is_empty() {
+        return Ok(image);
+    }
+
+    let mut img = Image::from(image);
+
+    for operation in operations {
+        img.apply(operation)?;
+    }
+
+    Ok(DynamicImage::from(img))
+}
+
+/// Writes the output to the file, creating its directory, or to stdout.
+///
+/// Files are replaced atomically, so an interrupted run never leaves a truncated output, with
+/// attributes of the input already set.
+fn write_output(path: &Path, data: &[u8], attributes: Option<&Attributes>) -> io::Result<()> {
+    if is_stdio(path) {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(data)?;
+        stdout.flush()
+    } else {
+        atomic::write_with(path, data, |temp| match attributes {
+            Some(attributes) => attributes.apply(temp),
+            None => Ok(()),
+        })
+    }
+}
+
+/// Keeps the input under the backup path as a hard link, falling back to a copy, so the input
+/// stays in place until it's replaced.
+fn backup_input(in_path: &Path, backup_path: &Path) -> io::Result<()> {
+    match fs::remove_file(backup_path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    fs::hard_link(in_path, backup_path).or_else(|_| fs::copy(in_path, backup_path).map(|_| ()))
+}
+
+/// Path of the original kept in place of the output, with the extension of the input.
+fn original_path(in_path: &Path, out_path: &Path) -> PathBuf {
+    out_path.with_extension(in_path.extension().unwrap_or_default())
+}
+
+/// Files the input may be written to, the output and the original kept in its place.
+///
+/// Pages are numbered from the output the same way for every input and with a different
+/// separator than renamed outputs, so they collide only when outputs do and the output stands
+/// for them.
+pub fn written_paths(
+    in_path: &Path,
+    out_path: &Path,
+    pages: Pages,
+    min_savings: Option<MinSavings>,
+) -> Vec<PathBuf> {
+    match min_savings {
+        Some(_) if pages != Pages::All => {
+            vec![out_path.to_path_buf(), original_path(in_path, out_path)]
+        }
+        _ => vec![out_path.to_path_buf()],
+    }
+}
+
+/// Writes the original image in place of the output, keeping its extension as it may be encoded
+/// with different codec. Nothing is copied when the original already is at that place.
+///
+/// Returns the path the original was written to.
+fn keep_original(
+    in_path: &Path,
+    out_path: &Path,
+    attributes: Option<&Attributes>,
+) -> io::Result<PathBuf> {
+    if is_stdio(out_path) {
+        match is_stdio(in_path) {
+            true => write_output(out_path, stdin()?, None)?,
+            false => write_output(out_path, &fs::read(in_path)?, None)?,
+        };
+
+        return Ok(out_path.to_path_buf());
+    }
+
+    let out_path = original_path(in_path, out_path);
+
+    if out_path != in_path {
+        write_output(&out_path, &fs::read(in_path)?, attributes)?;
+    }
+
+    Ok(out_path)
 }
 
 fn optimize(
     in_path: &Path,
     out_path: &Path,
     conf: EncoderConfig,
-    backup: bool,
+    operations: &[Operation],
+    options: &Options,
+    report: &mut FileReport,
+    progress: &Progress,
 ) -> Result<(), Box<dyn Error>> {
-    let decoder = Decoder::from_path(in_path)?;
+    let pages = options.pages;
+    let backup_path = PathBuf::from(format!("{}.backup", in_path.as_os_str().to_str().unwrap()));
 
-    if backup {
-        fs::rename(
-            in_path,
-            format!("{}.backup", in_path.as_os_str().to_str().unwrap()),
-        )?;
-    }
+    let (images, original_size) = if is_stdio(in_path) {
+        let images = decode_stdin(Some(&conf), operations, options.dpi, pages)?;
+
+        (images, stdin()?.len() as u64)
+    } else {
+        let decoder = configure(
+            Decoder::from_path(in_path)?,
+            Some(&conf),
+            operations,
+            options.dpi,
+        );
+        let original_size = fs::metadata(in_path)?.len();
+
+        if let Some((extension, content)) = decoder.format_mismatch() {
+            progress.println(format_args!(
+                "{in_path:?}: warning, extension suggests {extension:?} but content is {content:?}"
+            ));
+        }
+
+        (decode(decoder, pages)?, original_size)
+    };
+
+    report.input_size = Some(original_size);
+
+    // read before the input may be replaced
+    let attributes = match options.preserve.is_empty() || is_stdio(in_path) {
+        true => None,
+        false => Some(Attributes::read(in_path, options.preserve)?),
+    };
+    report.width = images.first().map(|image| image.width());
+    report.height = images.first().map(|image| image.height());
+
+    let count = images.len();
+    let mut skipped = 0;
+    let mut kept = 0;
+    let mut backed_up = false;
+
+    for (page, image) in images.into_iter().enumerate() {
+        let out_path = match pages {
+            Pages::All => page_path(out_path, page),
+            _ => out_path.to_path_buf(),
+        };
+
+        let (width, height) = (image.width(), image.height());
+        let image = apply_operations(image, operations)?;
 
-    let image = decoder.decode()?;
+        let (out_width, out_height) = output_size(&conf, &image);
+        let page = match pages {
+            Pages::All => Some(page),
+            Pages::Index(page) => Some(page),
+            Pages::Default => None,
+        };
 
-    fs::create_dir_all(out_path.parent().unwrap())?;
-    let out_file = File::create(out_path)?;
+        let mut data = vec![];
+        let (file, stages) = (in_path.to_path_buf(), progress.clone());
 
-    let encoder = Encoder::new(out_file, image).with_config(conf);
-    encoder.encode()?;
+        match Encoder::new(&mut data, image)
+            .with_config(conf.clone())
+            .with_progress(move |stage| stages.stage(&file, stage))
+            .encode()
+        {
+            Err(e) if rimage::error::is_quality_too_low(&e) => {
+                skipped += 1;
+
+                match page {
+                    Some(page) if pages == Pages::All => progress.println(format_args!(
+                        "{in_path:?}: page {page} skipped, quantization can't reach minimum quality"
+                    )),
+                    _ => progress.println(format_args!(
+                        "{in_path:?}: skipped, quantization can't reach minimum quality"
+                    )),
+                }
+
+                continue;
+            }
+            result => result?,
+        }
+
+        if let Some(min_savings) = options.min_savings {
+            let size = data.len() as u64;
+
+            if !min_savings.is_met(original_size, size) {
+                skipped += 1;
+
+                match page {
+                    Some(page) if pages == Pages::All => progress.println(format_args!(
+                        "{in_path:?}: page {page} skipped, output is {size} bytes and original is {original_size} bytes"
+                    )),
+                    _ => {
+                        kept += 1;
+                        report.outputs.push(OutputReport {
+                            path: keep_original(in_path, &out_path, attributes.as_ref())?,
+                            page,
+                            size: original_size,
+                            width,
+                            height,
+                        });
+
+                        progress.println(format_args!(
+                            "{in_path:?}: kept original, output is {size} bytes and original is {original_size} bytes"
+                        ));
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        // the input is about to be replaced, keep it before
+        if options.backup && !backed_up && out_path == in_path {
+            backup_input(in_path, &backup_path)?;
+            backed_up = true;
+        }
+
+        write_output(&out_path, &data, attributes.as_ref())?;
+        report.outputs.push(OutputReport {
+            path: out_path,
+            page,
+            size: data.len() as u64,
+            width: out_width,
+            height: out_height,
+        });
+    }
+
+    report.status = match (skipped, kept) {
+        (skipped, _) if skipped < count => Status::Optimized,
+        (_, 0) => Status::Skipped,
+        _ => Status::KeptOriginal,
+    };
+
+    // the input is backed up only once outputs are written, so it's never missing
+    if options.backup && !backed_up && skipped < count && !is_stdio(in_path) {
+        fs::rename(in_path, &backup_path)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,263 @@
+//! Detection of inputs written to the same output.
+
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use super::is_stdio;
+
+/// What to do when multiple inputs would be written to the same output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Collision {
+    /// Fails before any output is written.
+    #[default]
+    Error,
+
+    /// Appends a number to outputs of later inputs, `logo.jpg` becomes `logo_1.jpg`.
+    Rename,
+
+    /// Writes every input, the last one wins.
+    Overwrite,
+}
+
+impl FromStr for Collision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "error" => Self::Error,
+            "rename" => Self::Rename,
+            "overwrite" => Self::Overwrite,
+
+            policy => return Err(format!("{policy} is not valid collision policy")),
+        })
+    }
+}
+
+/// Path with a number appended to the file name.
+///
+/// The number is separated with an underscore, so renamed outputs never look like pages of
+/// another output, which are numbered with a dash.
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!("_{number}"));
+
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+/// Normalizes the path so different spellings of the same file compare equal.
+///
+/// Relative paths are resolved against the current directory, `.` and `..` are removed without
+/// following symlinks and the case is folded on platforms with case-insensitive file systems.
+fn normalize(path: &Path) -> PathBuf {
+    let absolute = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => env::current_dir().unwrap_or_default().join(path),
+    };
+
+    let mut normalized = PathBuf::new();
+
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    match cfg!(any(windows, target_os = "macos")) {
+        true => PathBuf::from(normalized.to_string_lossy().to_lowercase()),
+        false => normalized,
+    }
+}
+
+/// Finds outputs shared by multiple inputs and resolves them per the policy.
+///
+/// `written` lists every file an input may be written to given its output, such as the original
+/// kept when the output isn't smaller, these collide the same way as outputs.
+///
+/// # Returns
+///
+/// Returns the input and output pairs in the same order, or an error naming the inputs
+/// written to the same file.
+pub fn resolve_collisions(
+    paths: Vec<(PathBuf, PathBuf)>,
+    policy: Collision,
+    written: impl Fn(&Path, &Path) -> Vec<PathBuf>,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    if policy == Collision::Overwrite {
+        return Ok(paths);
+    }
+
+    let claims = |input: &Path, output: &Path| -> Vec<PathBuf> {
+        written(input, output)
+            .iter()
+            .map(|path| normalize(path))
+            .collect()
+    };
+
+    let outputs: HashSet<_> = paths
+        .iter()
+        .filter(|(_, output)| !is_stdio(output))
+        .flat_map(|(input, output)| claims(input, output))
+        .collect();
+    let mut taken: HashMap<PathBuf, (PathBuf, PathBuf)> = HashMap::new();
+
+    paths
+        .into_iter()
+        .map(|(input, output)| {
+            // everything read from stdin goes to stdout
+            if is_stdio(&output) {
+                return Ok((input, output));
+            }
+
+            let mut files = claims(&input, &output);
+            let other = files.iter().find_map(|file| taken.get(file));
+
+            let output = match other {
+                None => output,
+                Some((other, file)) if policy == Collision::Error => {
+                    return Err(format!(
+                        "{other:?} and {input:?} would both be written to {file:?}"
+                    ))
+                }
+                Some(_) => {
+                    let output = (1..)
+                        .map(|number| numbered_path(&output, number))
+                        .find(|path| {
+                            claims(&input, path)
+                                .iter()
+                                .all(|file| !outputs.contains(file) && !taken.contains_key(file))
+                        })
+                        .unwrap();
+
+                    files = claims(&input, &output);
+                    output
+                }
+            };
+
+            for (file, path) in files.into_iter().zip(written(&input, &output)) {
+                taken.insert(file, (input.clone(), path));
+            }
+
+            Ok((input, output))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(pairs: &[(&str, &str)]) -> Vec<(PathBuf, PathBuf)> {
+        pairs
+            .iter()
+            .map(|(input, output)| (PathBuf::from(input), PathBuf::from(output)))
+            .collect()
+    }
+
+    fn outputs(_: &Path, output: &Path) -> Vec<PathBuf> {
+        vec![output.to_path_buf()]
+    }
+
+    #[test]
+    fn detect_collisions() {
+        let colliding = paths(&[
+            ("logo.jpg", "logo.jpg"),
+            ("logo.png", "logo.jpg"),
+            ("logo.webp", "logo.jpg"),
+            ("logo_1.png", "logo_1.jpg"),
+        ]);
+
+        assert!(resolve_collisions(colliding.clone(), Collision::Error, outputs).is_err());
+
+        assert_eq!(
+            resolve_collisions(colliding.clone(), Collision::Rename, outputs).unwrap(),
+            paths(&[
+                ("logo.jpg", "logo.jpg"),
+                ("logo.png", "logo_2.jpg"),
+                ("logo.webp", "logo_3.jpg"),
+                ("logo_1.png", "logo_1.jpg"),
+            ])
+        );
+
+        assert_eq!(
+            resolve_collisions(colliding.clone(), Collision::Overwrite, outputs).unwrap(),
+            colliding
+        );
+
+        let distinct = paths(&[("a.png", "a.jpg"), ("b.png", "b.jpg"), ("-", "-")]);
+        assert_eq!(
+            resolve_collisions(distinct.clone(), Collision::Error, outputs).unwrap(),
+            distinct
+        );
+    }
+
+    #[test]
+    fn normalize_paths() {
+        let same = paths(&[("a.png", "./out/a.jpg"), ("b/a.png", "out/sub/../a.jpg")]);
+        assert!(resolve_collisions(same, Collision::Error, outputs).is_err());
+
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(
+            normalize(Path::new("./out/../a.jpg")),
+            normalize(&cwd.join("a.jpg"))
+        );
+    }
+
+    #[test]
+    fn detect_collisions_of_written_files() {
+        // the original is kept with its own extension next to the output
+        let originals = |input: &Path, output: &Path| {
+            vec![
+                output.to_path_buf(),
+                output.with_extension(input.extension().unwrap()),
+            ]
+        };
+
+        let colliding = paths(&[("a.png", "a.jpg"), ("b/a.jpg", "a.png")]);
+        assert!(resolve_collisions(colliding.clone(), Collision::Error, outputs).is_ok());
+
+        let error = resolve_collisions(colliding.clone(), Collision::Error, originals).unwrap_err();
+        assert!(error.contains(r#""b/a.jpg""#), "{error}");
+
+        assert_eq!(
+            resolve_collisions(colliding, Collision::Rename, originals).unwrap(),
+            paths(&[("a.png", "a.jpg"), ("b/a.jpg", "a_1.png")])
+        );
+    }
+
+    #[test]
+    fn rename_apart_from_pages() {
+        // pages of every input are written with the index appended to the output
+        let pages = |_: &Path, output: &Path| {
+            let stem = output.file_stem().unwrap().to_str().unwrap();
+            (0..3)
+                .map(|page| output.with_file_name(format!("{stem}-{page}.png")))
+                .collect::<Vec<_>>()
+        };
+
+        let colliding = paths(&[("scan.tiff", "scan.png"), ("b/scan.tiff", "scan.png")]);
+        let resolved = resolve_collisions(colliding, Collision::Rename, outputs).unwrap();
+        assert_eq!(
+            resolved,
+            paths(&[("scan.tiff", "scan.png"), ("b/scan.tiff", "scan_1.png")])
+        );
+
+        let written: HashSet<_> = resolved
+            .iter()
+            .flat_map(|(input, output)| pages(input, output))
+            .collect();
+        assert_eq!(written.len(), 6);
+    }
+}
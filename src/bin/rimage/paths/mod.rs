@@ -1,46 +1,40 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+pub use collision::{resolve_collisions, Collision};
+pub use template::{Placeholder, Template, Values};
+pub use walk::{glob_set, WalkOptions};
+
+mod collision;
+mod template;
+mod walk;
+
+/// Path standing for stdin when used as input and for stdout when used as output.
+pub const STDIO: &str = "-";
+
+/// Checks whether the path stands for stdin or stdout.
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO)
+}
+
 #[cfg(not(feature = "parallel"))]
 pub fn get_paths(
     files: Vec<PathBuf>,
     out_dir: Option<PathBuf>,
     suffix: Option<String>,
     extension: impl ToString,
-    recursive: bool,
+    common_path: Option<PathBuf>,
 ) -> impl Iterator<Item = (PathBuf, PathBuf)> {
-    let common_path = if recursive {
-        get_common_path(&files)
-    } else {
-        None
-    };
-
     files.into_iter().map(move |path| -> (PathBuf, PathBuf) {
-        let file_name = path
-            .file_stem()
-            .and_then(|f| f.to_str())
-            .unwrap_or("optimized_image");
-
-        let mut out_path = match &out_dir {
-            Some(dir) => {
-                if let Some(common) = &common_path {
-                    let relative_path =
-                        path.parent().unwrap().strip_prefix(common).unwrap_or(&path);
-                    dir.join(relative_path)
-                } else {
-                    dir.clone()
-                }
-            }
-            None => path.parent().map(|p| p.to_path_buf()).unwrap_or_default(),
-        };
-
-        if let Some(s) = &suffix {
-            out_path.push(format!("{file_name}{s}.{}", extension.to_string()));
-        } else {
-            out_path.push(format!("{file_name}.{}", extension.to_string()));
-        }
+        let out_path = output_path(
+            &path,
+            out_dir.as_deref(),
+            common_path.as_deref(),
+            suffix.as_deref(),
+            &extension.to_string(),
+        );
 
         (path, out_path)
     })
@@ -52,45 +46,79 @@ pub fn get_paths(
     out_dir: Option<PathBuf>,
     suffix: Option<String>,
     extension: impl ToString + Sync + Send,
-    recursive: bool,
+    common_path: Option<PathBuf>,
 ) -> impl ParallelIterator<Item = (PathBuf, PathBuf)> {
-    let common_path = if recursive {
-        get_common_path(&files)
-    } else {
-        None
-    };
-
     files
         .into_par_iter()
         .map(move |path| -> (PathBuf, PathBuf) {
-            let file_name = path
-                .file_stem()
-                .and_then(|f| f.to_str())
-                .unwrap_or("optimized_image");
-
-            let mut out_path = match &out_dir {
-                Some(dir) => {
-                    if let Some(common) = &common_path {
-                        let relative_path =
-                            path.parent().unwrap().strip_prefix(common).unwrap_or(&path);
-                        dir.join(relative_path)
-                    } else {
-                        dir.clone()
-                    }
-                }
-                None => path.parent().map(|p| p.to_path_buf()).unwrap_or_default(),
-            };
-
-            if let Some(s) = &suffix {
-                out_path.push(format!("{file_name}{s}.{}", extension.to_string()));
+            let out_path = output_path(
+                &path,
+                out_dir.as_deref(),
+                common_path.as_deref(),
+                suffix.as_deref(),
+                &extension.to_string(),
+            );
+
+            (path, out_path)
+        })
+}
+
+/// Computes the output path of the input, placed in the output directory under its path
+/// relative to the common path when set.
+fn output_path(
+    path: &Path,
+    out_dir: Option<&Path>,
+    common_path: Option<&Path>,
+    suffix: Option<&str>,
+    extension: &str,
+) -> PathBuf {
+    // images read from stdin have no name to derive the output from, so they go to stdout
+    if is_stdio(path) || out_dir.is_some_and(is_stdio) {
+        return PathBuf::from(STDIO);
+    }
+
+    let file_name = path
+        .file_stem()
+        .and_then(|f| f.to_str())
+        .unwrap_or("optimized_image");
+
+    let mut out_path = match out_dir {
+        Some(dir) => {
+            if let Some(common) = common_path {
+                let relative_path = path.parent().unwrap().strip_prefix(common).unwrap_or(path);
+                dir.join(relative_path)
             } else {
-                out_path.push(format!("{file_name}.{}", extension.to_string()));
+                dir.to_path_buf()
             }
+        }
+        None => path.parent().map(|p| p.to_path_buf()).unwrap_or_default(),
+    };
 
-            (path, out_path)
+    if let Some(s) = suffix {
+        out_path.push(format!("{file_name}{s}.{extension}"));
+    } else {
+        out_path.push(format!("{file_name}.{extension}"));
+    }
+
+    out_path
+}
+
+/// Finds the directory outputs of recursively processed inputs are placed relative to, the
+/// common path of input directories and directories containing input files.
+pub fn get_input_root(inputs: &[PathBuf]) -> Option<PathBuf> {
+    let dirs: Vec<_> = inputs
+        .iter()
+        .filter(|input| !is_stdio(input))
+        .map(|input| match input.is_dir() {
+            true => input.clone(),
+            false => input.parent().map(Path::to_path_buf).unwrap_or_default(),
         })
+        .collect();
+
+    get_common_path(&dirs)
 }
 
+/// Finds the longest path all paths start with.
 fn get_common_path(paths: &[PathBuf]) -> Option<PathBuf> {
     if paths.is_empty() {
         return None;
@@ -110,17 +138,20 @@ fn get_common_path(paths: &[PathBuf]) -> Option<PathBuf> {
     Some(common_path)
 }
 
-#[inline]
-pub fn collect_files(input: Vec<PathBuf>) -> Vec<PathBuf> {
+/// Collects input files, expanding glob patterns on Windows and directories on all platforms.
+pub fn collect_files(input: Vec<PathBuf>, options: &WalkOptions) -> Vec<PathBuf> {
     #[cfg(windows)]
     #[cfg(not(feature = "parallel"))]
-    let input = input.into_iter().flat_map(apply_glob_pattern).collect();
+    let input: Vec<_> = input.into_iter().flat_map(apply_glob_pattern).collect();
 
     #[cfg(windows)]
     #[cfg(feature = "parallel")]
-    let input = input.into_par_iter().flat_map(apply_glob_pattern).collect();
+    let input: Vec<_> = input.into_par_iter().flat_map(apply_glob_pattern).collect();
 
     input
+        .into_iter()
+        .flat_map(|path| walk::walk(path, options))
+        .collect()
 }
 
 #[cfg(windows)]
@@ -0,0 +1,229 @@
+//! Templates of output file names.
+
+use std::{
+    path::{self, Path},
+    str::FromStr,
+};
+
+/// Value of the input file substituted into a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// File name of the input without extension.
+    Stem,
+
+    /// Extension of the input.
+    Ext,
+
+    /// Width of the output.
+    Width,
+
+    /// Height of the output.
+    Height,
+
+    /// Codec of the output.
+    Codec,
+
+    /// Quality of the output.
+    Quality,
+
+    /// First 8 hex digits of the input content hash.
+    Hash8,
+
+    /// Name of the directory containing the input.
+    Dir,
+}
+
+impl FromStr for Placeholder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "stem" => Self::Stem,
+            "ext" => Self::Ext,
+            "width" => Self::Width,
+            "height" => Self::Height,
+            "codec" => Self::Codec,
+            "quality" => Self::Quality,
+            "hash8" => Self::Hash8,
+            "dir" => Self::Dir,
+
+            name => return Err(format!("{{{name}}} is not valid placeholder")),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Template of output file names without extension, such as `{stem}-{width}w`.
+///
+/// Outputs are kept in the output directory, so neither the template nor the values rendered
+/// into it can contain path separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template(Vec<Part>);
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("template of file names can't be empty".to_string());
+        }
+
+        if s.contains(path::is_separator) {
+            return Err(format!("{s} can't contain path separators"));
+        }
+
+        let mut parts = vec![];
+        let mut rest = s;
+
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| format!("{s} has unclosed placeholder"))?;
+
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+
+            parts.push(Part::Placeholder(rest[start + 1..start + end].parse()?));
+            rest = &rest[start + end + 1..];
+        }
+
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+
+        Ok(Self(parts))
+    }
+}
+
+/// Values of placeholders for an input file.
+#[derive(Debug, Clone, Default)]
+pub struct Values {
+    pub stem: String,
+    pub ext: String,
+    pub dir: String,
+    pub codec: String,
+    pub quality: f32,
+
+    /// Output dimensions, needed only by templates with width or height.
+    pub dimensions: Option<(u32, u32)>,
+
+    /// Input content hash, needed only by templates with hash.
+    pub hash: Option<String>,
+}
+
+impl Values {
+    /// Takes values derived from the input path, dimensions and hash are left out.
+    pub fn new(path: &Path, codec: &str, quality: f32) -> Self {
+        let name = |name: Option<&std::ffi::OsStr>| {
+            name.map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        Self {
+            stem: name(path.file_stem()),
+            ext: name(path.extension()),
+            dir: name(path.parent().and_then(Path::file_name)),
+            codec: codec.to_string(),
+            quality,
+            dimensions: None,
+            hash: None,
+        }
+    }
+}
+
+impl Template {
+    /// Checks whether the template contains the placeholder.
+    pub fn contains(&self, placeholder: Placeholder) -> bool {
+        self.0.contains(&Part::Placeholder(placeholder))
+    }
+
+    /// Builds the file name with placeholders replaced by the values.
+    ///
+    /// Path separators in values are replaced with underscores.
+    pub fn render(&self, values: &Values) -> String {
+        let (width, height) = values.dimensions.unwrap_or_default();
+
+        self.0
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Placeholder(placeholder) => match placeholder {
+                    Placeholder::Stem => values.stem.clone(),
+                    Placeholder::Ext => values.ext.clone(),
+                    Placeholder::Width => width.to_string(),
+                    Placeholder::Height => height.to_string(),
+                    Placeholder::Codec => values.codec.clone(),
+                    Placeholder::Quality => values.quality.to_string(),
+                    Placeholder::Hash8 => values
+                        .hash
+                        .as_deref()
+                        .unwrap_or_default()
+                        .chars()
+                        .take(8)
+                        .collect(),
+                    Placeholder::Dir => values.dir.clone(),
+                }
+                .replace(path::is_separator, "_"),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let mut values = Values::new(Path::new("photos/2024/logo.png"), "webp", 80.0);
+        values.dimensions = Some((640, 480));
+        values.hash = Some("0123456789abcdef".to_string());
+
+        let template: Template = "{dir}-{stem}.{ext}-{width}x{height}-{codec}{quality}-{hash8}"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            template.render(&values),
+            "2024-logo.png-640x480-webp80-01234567"
+        );
+        assert!(template.contains(Placeholder::Width));
+        assert!(!"{stem}"
+            .parse::<Template>()
+            .unwrap()
+            .contains(Placeholder::Hash8));
+    }
+
+    #[test]
+    fn invalid_template() {
+        assert!("".parse::<Template>().is_err());
+        assert!("{stem".parse::<Template>().is_err());
+        assert!("{size}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn template_outside_directory() {
+        assert!("../{stem}".parse::<Template>().is_err());
+        assert!("/tmp/{stem}".parse::<Template>().is_err());
+        assert!("{dir}/{stem}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn values_outside_directory() {
+        let values = Values {
+            stem: "../../logo".to_string(),
+            dir: "/etc".to_string(),
+            codec: "webp".to_string(),
+            ..Default::default()
+        };
+
+        let template: Template = "{dir}-{stem}".parse().unwrap();
+        assert_eq!(template.render(&values), "_etc-.._.._logo");
+    }
+}
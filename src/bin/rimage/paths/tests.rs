@@ -29,3 +29,76 @@ fn find_common_path() {
     let common_path = get_common_path(&paths);
     assert_eq!(common_path, Some(PathBuf::from("/path")));
 }
+
+#[test]
+fn find_input_root() {
+    let root = get_input_root(&[PathBuf::from("tests/files")]);
+    assert_eq!(root, Some(PathBuf::from("tests/files")));
+
+    let root = get_input_root(&[PathBuf::from("tests/files/png/f1t.png")]);
+    assert_eq!(root, Some(PathBuf::from("tests/files/png")));
+
+    let root = get_input_root(&[
+        PathBuf::from("tests/files/png"),
+        PathBuf::from("tests/files/jpg/f1t.jpg"),
+        PathBuf::from(STDIO),
+    ]);
+    assert_eq!(root, Some(PathBuf::from("tests/files")));
+}
+
+#[test]
+fn recursive_paths() {
+    let paths: Vec<_> = get_paths(
+        vec![
+            PathBuf::from("photos/2024/a.jpg"),
+            PathBuf::from("photos/2024/may/b.jpg"),
+        ],
+        Some(PathBuf::from("out")),
+        None,
+        "png",
+        Some(PathBuf::from("photos")),
+    )
+    .collect();
+
+    assert_eq!(
+        paths,
+        [
+            (
+                PathBuf::from("photos/2024/a.jpg"),
+                PathBuf::from("out/2024/a.png")
+            ),
+            (
+                PathBuf::from("photos/2024/may/b.jpg"),
+                PathBuf::from("out/2024/may/b.png")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn stdio_paths() {
+    let paths: Vec<_> = get_paths(
+        vec![PathBuf::from(STDIO)],
+        Some(PathBuf::from("out")),
+        None,
+        "png",
+        None,
+    )
+    .collect();
+
+    assert_eq!(paths, [(PathBuf::from("-"), PathBuf::from("-"))]);
+
+    let paths: Vec<_> = get_paths(
+        vec![PathBuf::from("dir/image.jpg")],
+        Some(PathBuf::from(STDIO)),
+        None,
+        "png",
+        None,
+    )
+    .collect();
+
+    assert_eq!(
+        paths,
+        [(PathBuf::from("dir/image.jpg"), PathBuf::from("-"))]
+    );
+}
@@ -0,0 +1,303 @@
+//! Expansion of input directories into the image files they contain.
+
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rimage::config::ImageFormat;
+use walkdir::{DirEntry, WalkDir};
+
+use super::is_stdio;
+
+/// Filters applied while collecting input files.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Only files matching any of these patterns are processed.
+    pub include: Option<GlobSet>,
+
+    /// Files and directories matching any of these patterns are skipped.
+    pub exclude: Option<GlobSet>,
+
+    /// Extensions of files collected from directories, any supported image format when not set.
+    pub extensions: Option<Vec<String>>,
+
+    /// Descends into subdirectories, otherwise only files directly in the directory are collected.
+    pub recursive: bool,
+
+    /// Maximum depth of subdirectories to descend into.
+    pub max_depth: Option<usize>,
+
+    /// Follows symbolic links, otherwise they are skipped.
+    pub follow_symlinks: bool,
+
+    /// Collects hidden files and descends into hidden directories.
+    pub hidden: bool,
+}
+
+impl WalkOptions {
+    /// Checks whether the file passes the include and exclude patterns.
+    fn matches(&self, path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(path))
+            && !self.is_excluded(path)
+    }
+
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude
+            .as_ref()
+            .is_some_and(|exclude| exclude.is_match(path))
+    }
+
+    /// Checks whether the extension of a file found in a directory is accepted.
+    fn has_extension(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension() else {
+            return false;
+        };
+
+        match &self.extensions {
+            Some(extensions) => extensions
+                .iter()
+                .any(|e| extension.eq_ignore_ascii_case(e.trim_start_matches('.'))),
+            None => ImageFormat::from_ext(extension).is_ok(),
+        }
+    }
+
+    /// Depth of entries collected from a directory, files directly in it are at depth 1.
+    fn max_depth(&self) -> usize {
+        match self.max_depth {
+            Some(depth) if self.recursive => depth.saturating_add(1),
+            None if self.recursive => usize::MAX,
+            _ => 1,
+        }
+    }
+
+    /// Checks whether the file would be collected by walking the root directory, or is the root
+    /// itself.
+    pub fn accepts(&self, root: &Path, path: &Path) -> bool {
+        if path == root {
+            return self.matches(path);
+        }
+
+        let Ok(relative) = path.strip_prefix(root) else {
+            return false;
+        };
+
+        let depth = relative.components().count();
+
+        // directories are pruned by hidden-file policy and exclude patterns while walking
+        let mut entry = root.to_path_buf();
+        let pruned = relative.components().any(|component| {
+            entry.push(component);
+
+            (!self.hidden && component.as_os_str().to_string_lossy().starts_with('.'))
+                || self.is_excluded(&entry)
+        });
+
+        depth <= self.max_depth() && !pruned && self.has_extension(path) && self.matches(path)
+    }
+}
+
+/// Builds a set matching any of the patterns.
+pub fn glob_set(patterns: impl IntoIterator<Item = Glob>) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(pattern);
+    }
+
+    builder.build()
+}
+
+/// Expands a directory into the files it contains, sorted by name.
+///
+/// Files passed directly are kept regardless of extension and hidden-file policy, only include
+/// and exclude patterns apply to them.
+pub fn walk(path: PathBuf, options: &WalkOptions) -> Vec<PathBuf> {
+    if !path.is_dir() {
+        return if is_stdio(&path) || options.matches(&path) {
+            vec![path]
+        } else {
+            vec![]
+        };
+    }
+
+    WalkDir::new(&path)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth())
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || (options.hidden || !is_hidden(entry)) && !options.is_excluded(entry.path())
+        })
+        .filter_map(|entry| entry.map_err(|e| eprintln!("{path:?}: {e}")).ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(DirEntry::into_path)
+        .filter(|path| options.has_extension(path) && options.matches(path))
+        .collect()
+}
+
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry
+        .file_name()
+        .to_str()
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// Creates a directory tree with the files in a fresh temporary directory, removed on drop.
+    fn tree(files: &[&str]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, []).unwrap();
+        }
+
+        (dir, root)
+    }
+
+    fn names(root: &Path, paths: Vec<PathBuf>) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn walk_directory() {
+        let (_dir, root) = tree(&[
+            "b.png",
+            "a.jpg",
+            "notes.txt",
+            ".hidden.png",
+            "sub/c.png",
+            "sub/deep/d.png",
+        ]);
+
+        let files = walk(root.clone(), &WalkOptions::default());
+        assert_eq!(names(&root, files), ["a.jpg", "b.png"]);
+
+        let options = WalkOptions {
+            recursive: true,
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(
+            names(&root, files),
+            ["a.jpg", "b.png", "sub/c.png", "sub/deep/d.png"]
+        );
+
+        let options = WalkOptions {
+            recursive: true,
+            max_depth: Some(1),
+            hidden: true,
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(
+            names(&root, files),
+            [".hidden.png", "a.jpg", "b.png", "sub/c.png"]
+        );
+    }
+
+    #[test]
+    fn accept_paths() {
+        let root = Path::new("photos");
+        let options = WalkOptions {
+            recursive: true,
+            max_depth: Some(1),
+            exclude: Some(glob_set([Glob::new("**/thumbs").unwrap()]).unwrap()),
+            ..Default::default()
+        };
+
+        assert!(options.accepts(root, &root.join("a.png")));
+        assert!(options.accepts(root, &root.join("sub/b.jpg")));
+        assert!(!options.accepts(root, &root.join("sub/deep/c.png")));
+        assert!(!options.accepts(root, &root.join("thumbs/d.png")));
+        assert!(!options.accepts(root, &root.join(".cache/e.png")));
+        assert!(!options.accepts(root, &root.join(".a.png.123-0.tmp")));
+        assert!(!options.accepts(root, &root.join("a.png.backup")));
+        assert!(!options.accepts(root, Path::new("other/a.png")));
+    }
+
+    #[test]
+    fn walk_with_filters() {
+        let (_dir, root) = tree(&["a.jpg", "b.PNG", "c.tmp", "thumbs/d.png", "sub/e.jpg"]);
+
+        let options = WalkOptions {
+            recursive: true,
+            exclude: Some(glob_set([Glob::new("**/thumbs").unwrap()]).unwrap()),
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(names(&root, files), ["a.jpg", "b.PNG", "sub/e.jpg"]);
+
+        let options = WalkOptions {
+            recursive: true,
+            include: Some(glob_set([Glob::new("*.jpg").unwrap()]).unwrap()),
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(names(&root, files), ["a.jpg", "sub/e.jpg"]);
+
+        let options = WalkOptions {
+            extensions: Some(vec!["tmp".to_string(), ".png".to_string()]),
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(names(&root, files), ["b.PNG", "c.tmp"]);
+    }
+
+    #[test]
+    fn keep_explicit_files() {
+        let (_dir, root) = tree(&[".hidden", "a.jpg"]);
+
+        let files = walk(root.join(".hidden"), &WalkOptions::default());
+        assert_eq!(names(&root, files), [".hidden"]);
+
+        let options = WalkOptions {
+            exclude: Some(glob_set([Glob::new("*.jpg").unwrap()]).unwrap()),
+            ..Default::default()
+        };
+        assert!(walk(root.join("a.jpg"), &options).is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn skip_symlinks() {
+        let (_dir, root) = tree(&["a.png", "dir/b.png"]);
+        std::os::unix::fs::symlink(root.join("a.png"), root.join("link.png")).unwrap();
+        std::os::unix::fs::symlink(root.join("dir"), root.join("linked")).unwrap();
+
+        let options = WalkOptions {
+            recursive: true,
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(names(&root, files), ["a.png", "dir/b.png"]);
+
+        let options = WalkOptions {
+            recursive: true,
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let files = walk(root.clone(), &options);
+        assert_eq!(
+            names(&root, files),
+            ["a.png", "dir/b.png", "link.png", "linked/b.png"]
+        );
+    }
+}
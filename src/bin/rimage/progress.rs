@@ -0,0 +1,97 @@
+use std::{
+    fmt::Display,
+    path::Path,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+};
+
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
+use rimage::EncodingStage;
+
+use crate::report::FileReport;
+
+/// Progress bar of processed files, drawn on stderr only when it's a terminal.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    bar: ProgressBar,
+    saved: Arc<AtomicI64>,
+}
+
+impl Progress {
+    /// Creates a progress bar for the number of files, which is never drawn when disabled.
+    pub fn new(files: usize, enabled: bool) -> Self {
+        let bar = match enabled {
+            true => ProgressBar::new(files as u64),
+            false => ProgressBar::hidden(),
+        };
+
+        bar.set_style(
+            ProgressStyle::with_template(
+                "{bar:30} {pos}/{len} files, saved {prefix} [{elapsed_precise}, ETA {eta}] {wide_msg}",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+        );
+        bar.set_prefix(format_saved(0));
+
+        Self {
+            bar,
+            saved: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /// Shows the file as currently processed.
+    pub fn start(&self, file: &Path) {
+        self.bar.set_message(file.display().to_string());
+    }
+
+    /// Shows the encoding stage of the currently processed file.
+    pub fn stage(&self, file: &Path, stage: EncodingStage) {
+        self.bar
+            .set_message(format!("{} ({stage})", file.display()));
+    }
+
+    /// Counts the processed file and the bytes its outputs saved.
+    pub fn finish_file(&self, report: &FileReport) {
+        if let (Some(input_size), false) = (report.input_size, report.outputs.is_empty()) {
+            let output_size: u64 = report.outputs.iter().map(|output| output.size).sum();
+            let saved = input_size as i64 - output_size as i64;
+
+            let total = self.saved.fetch_add(saved, Ordering::Relaxed) + saved;
+            self.bar.set_prefix(format_saved(total));
+        }
+
+        self.bar.inc(1);
+    }
+
+    /// Prints the message to stderr without breaking the progress bar.
+    pub fn println(&self, message: impl Display) {
+        self.bar.suspend(|| eprintln!("{message}"));
+    }
+
+    /// Removes the progress bar.
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+fn format_saved(saved: i64) -> String {
+    match saved {
+        0.. => HumanBytes(saved as u64).to_string(),
+        _ => format!("-{}", HumanBytes(saved.unsigned_abs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_bytes() {
+        assert_eq!(format_saved(0), "0 B");
+        assert_eq!(format_saved(2048), "2.00 KiB");
+        assert_eq!(format_saved(-2048), "-2.00 KiB");
+    }
+}
@@ -0,0 +1,51 @@
+use std::fs;
+
+use rimage::config::Palette;
+
+/// Loads a fixed palette from a GIMP palette or hex colors file.
+pub fn parse_palette(path: &str) -> Result<Palette, String> {
+    fs::read_to_string(path)
+        .map_err(|e| format!("{path}: {e}"))?
+        .parse()
+}
+
+/// Parses quantization quality in `max` or pngquant-like `min-max` format.
+pub fn parse_quality_range(s: &str) -> Result<(u8, u8), String> {
+    let parse = |n: &str| -> Result<u8, String> {
+        match n.trim().parse() {
+            Ok(quality) if quality <= 100 => Ok(quality),
+            _ => Err(format!(
+                "{n} is not valid quality, expected number in range 0 - 100"
+            )),
+        }
+    };
+
+    match s.split_once('-') {
+        Some((min, max)) => {
+            let (min, max) = (parse(min)?, parse(max)?);
+
+            if min > max {
+                return Err(format!(
+                    "{s} is not valid quality range, minimum is greater than maximum"
+                ));
+            }
+
+            Ok((min, max))
+        }
+        None => Ok((0, parse(s)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quality_range() {
+        assert_eq!(parse_quality_range("75"), Ok((0, 75)));
+        assert_eq!(parse_quality_range("65-80"), Ok((65, 80)));
+        assert!(parse_quality_range("80-65").is_err());
+        assert!(parse_quality_range("101").is_err());
+        assert!(parse_quality_range("a-80").is_err());
+    }
+}
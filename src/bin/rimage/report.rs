@@ -0,0 +1,247 @@
+use std::{path::PathBuf, time::Duration};
+
+use rimage::{config::EncoderConfig, Operation};
+use serde::Serialize;
+
+/// Outcome of processing an input file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    /// At least one output was written.
+    #[default]
+    Optimized,
+
+    /// The input is unchanged since the run recorded in the manifest, nothing was written.
+    Unchanged,
+
+    /// The original image was kept, as the output wasn't small enough.
+    KeptOriginal,
+
+    /// Quantization couldn't reach minimum quality, nothing was written.
+    Skipped,
+
+    /// Processing failed.
+    Failed,
+}
+
+/// Report of a processed input file.
+#[derive(Debug, Default, Serialize)]
+pub struct FileReport {
+    pub input: PathBuf,
+    pub status: Status,
+    pub input_size: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub outputs: Vec<OutputReport>,
+
+    /// Processing time in seconds.
+    pub duration: f64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Report of a written output file.
+#[derive(Debug, Serialize)]
+pub struct OutputReport {
+    pub path: PathBuf,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<usize>,
+
+    pub size: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Input to output mapping planned by a dry run.
+#[derive(Debug, Serialize)]
+pub struct PlannedFile {
+    pub input: PathBuf,
+    pub output: PathBuf,
+}
+
+/// Settings images are encoded with.
+#[derive(Debug, Serialize)]
+struct Settings {
+    codec: String,
+    quality: f32,
+    quantization: Option<QuantizationSettings>,
+    shared_palette: bool,
+    resize: Option<ResizeSettings>,
+    tiff_compression: String,
+    tone_mapping: String,
+    operations: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct QuantizationSettings {
+    quality: u8,
+    min_quality: u8,
+    max_colors: u32,
+    speed: u8,
+    dithering: f32,
+    dithering_method: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ResizeSettings {
+    width: Option<usize>,
+    height: Option<usize>,
+    filter: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Totals {
+    files: usize,
+    optimized: usize,
+    unchanged: usize,
+    kept_original: usize,
+    skipped: usize,
+    failed: usize,
+    input_size: u64,
+    output_size: u64,
+    saved: i64,
+    duration: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    settings: Settings,
+    files: &'a [FileReport],
+    totals: Totals,
+}
+
+/// Name of the value as used by the CLI, `FloydSteinberg` becomes `floyd-steinberg`.
+fn name(value: &impl std::fmt::Debug) -> String {
+    let mut name = String::new();
+
+    for (i, c) in format!("{value:?}").chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('-');
+        }
+
+        name.push(c.to_ascii_lowercase());
+    }
+
+    name
+}
+
+impl Settings {
+    fn new(conf: &EncoderConfig, operations: &[Operation]) -> Self {
+        Self {
+            // codecs are named without separators
+            codec: format!("{:?}", conf.codec()).to_lowercase(),
+            quality: conf.quality(),
+            quantization: conf
+                .quantization_config()
+                .map(|quantization| QuantizationSettings {
+                    quality: quantization.quality(),
+                    min_quality: quantization.min_quality(),
+                    max_colors: quantization.max_colors(),
+                    speed: quantization.speed(),
+                    dithering: quantization.dithering_level(),
+                    dithering_method: name(&quantization.dithering_method()),
+                }),
+            shared_palette: conf.palette().is_some(),
+            resize: conf.resize_config().map(|resize| ResizeSettings {
+                width: resize.width(),
+                height: resize.height(),
+                filter: name(&resize.filter_type()),
+            }),
+            tiff_compression: name(&conf.tiff_compression()),
+            tone_mapping: name(&conf.tone_mapping().operator()),
+            operations: operations
+                .iter()
+                .map(|operation| format!("{operation:?}"))
+                .collect(),
+        }
+    }
+}
+
+/// Builds the JSON report of the run with settings, processed files and totals.
+pub fn json_report(
+    conf: &EncoderConfig,
+    operations: &[Operation],
+    files: &[FileReport],
+    duration: Duration,
+) -> serde_json::Result<String> {
+    let written = files.iter().filter(|file| !file.outputs.is_empty());
+
+    let input_size: u64 = written.clone().filter_map(|file| file.input_size).sum();
+    let output_size: u64 = written
+        .flat_map(|file| &file.outputs)
+        .map(|output| output.size)
+        .sum();
+
+    let count = |status| files.iter().filter(|file| file.status == status).count();
+
+    let report = Report {
+        settings: Settings::new(conf, operations),
+        files,
+        totals: Totals {
+            files: files.len(),
+            optimized: count(Status::Optimized),
+            unchanged: count(Status::Unchanged),
+            kept_original: count(Status::KeptOriginal),
+            skipped: count(Status::Skipped),
+            failed: count(Status::Failed),
+            input_size,
+            output_size,
+            saved: input_size as i64 - output_size as i64,
+            duration: duration.as_secs_f64(),
+        },
+    };
+
+    serde_json::to_string_pretty(&report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_totals() {
+        let files = [
+            FileReport {
+                input: PathBuf::from("a.png"),
+                input_size: Some(1000),
+                outputs: vec![OutputReport {
+                    path: PathBuf::from("a.jpg"),
+                    page: None,
+                    size: 400,
+                    width: 10,
+                    height: 10,
+                }],
+                ..Default::default()
+            },
+            FileReport {
+                input: PathBuf::from("b.png"),
+                status: Status::Failed,
+                input_size: Some(500),
+                error: Some("error".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let report = json_report(
+            &EncoderConfig::default(),
+            &[],
+            &files,
+            Duration::from_secs(2),
+        )
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(report["settings"]["codec"], "mozjpeg");
+        assert_eq!(report["settings"]["tone_mapping"], "aces");
+        assert_eq!(report["files"][0]["status"], "optimized");
+        assert_eq!(report["files"][0]["outputs"][0]["size"], 400);
+        assert_eq!(report["files"][1]["status"], "failed");
+        assert_eq!(report["files"][1]["error"], "error");
+        assert_eq!(report["totals"]["failed"], 1);
+        assert_eq!(report["totals"]["input_size"], 1000);
+        assert_eq!(report["totals"]["saved"], 600);
+        assert_eq!(report["totals"]["duration"], 2.0);
+    }
+}
@@ -0,0 +1,197 @@
+//! Watching inputs for new or modified images.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, SystemTime},
+};
+
+use notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
+
+use crate::paths::{collect_files, WalkOptions};
+
+/// Files written by the run with their size and modification time, so their events are ignored.
+#[derive(Debug, Default)]
+struct Written(HashMap<PathBuf, (u64, Option<SystemTime>)>);
+
+fn stamp(metadata: &Metadata) -> (u64, Option<SystemTime>) {
+    (metadata.len(), metadata.modified().ok())
+}
+
+impl Written {
+    fn record(&mut self, path: PathBuf) {
+        if let Ok(metadata) = fs::metadata(&path) {
+            self.0.insert(path, stamp(&metadata));
+        }
+    }
+
+    /// Checks whether the file is unchanged since it was written, forgetting it otherwise.
+    fn contains(&mut self, path: &Path) -> bool {
+        let unchanged = match (self.0.get(path), fs::metadata(path)) {
+            (Some(written), Ok(metadata)) => *written == stamp(&metadata),
+            _ => false,
+        };
+
+        if !unchanged {
+            self.0.remove(path);
+        }
+
+        unchanged
+    }
+}
+
+/// Input as passed by the user and its canonical path reported in events.
+#[derive(Debug)]
+struct Root {
+    input: PathBuf,
+    canonical: PathBuf,
+}
+
+impl Root {
+    /// Maps the path reported in events back to the path under the input.
+    fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        match path.strip_prefix(&self.canonical) {
+            Ok(relative) if relative.as_os_str().is_empty() => Some(self.input.clone()),
+            Ok(relative) => Some(self.input.join(relative)),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Watches the inputs until interrupted, calling `process` with images created or modified in
+/// them once writes to them settle for the debounce time.
+///
+/// `process` returns the files it wrote, which are not processed again unless modified
+/// afterwards, the same applies to the `written` files of the initial run.
+pub fn watch(
+    inputs: &[PathBuf],
+    options: &WalkOptions,
+    debounce: Duration,
+    written: Vec<PathBuf>,
+    mut process: impl FnMut(Vec<PathBuf>) -> Vec<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = mpsc::channel();
+    let mut debouncer = new_debouncer(debounce, tx)?;
+
+    let mode = match options.recursive {
+        true => RecursiveMode::Recursive,
+        false => RecursiveMode::NonRecursive,
+    };
+
+    let mut roots = vec![];
+
+    for input in inputs {
+        let canonical = input
+            .canonicalize()
+            .map_err(|e| format!("{input:?}: {e}"))?;
+
+        debouncer
+            .watcher()
+            .watch(&canonical, mode)
+            .map_err(|e| format!("{input:?}: {e}"))?;
+
+        roots.push(Root {
+            input: input.clone(),
+            canonical,
+        });
+    }
+
+    eprintln!("Watching for changes, press Ctrl-C to stop");
+
+    let mut own = Written::default();
+    written.into_iter().for_each(|path| own.record(path));
+
+    for result in rx {
+        let events = match result {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("{e}");
+                continue;
+            }
+        };
+
+        // files still being written are reported continuously, they're processed once settled
+        let mut files: Vec<_> = events
+            .into_iter()
+            .filter(|event| event.kind == DebouncedEventKind::Any)
+            .flat_map(|event| changed_files(&roots, options, &event.path))
+            .filter(|path| !own.contains(path))
+            .collect();
+
+        files.sort();
+        files.dedup();
+
+        if !files.is_empty() {
+            process(files).into_iter().for_each(|path| own.record(path));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects images at the changed path which would be collected from the inputs.
+fn changed_files(roots: &[Root], options: &WalkOptions, path: &Path) -> Vec<PathBuf> {
+    let Some((root, path)) = roots
+        .iter()
+        .find_map(|root| root.resolve(path).map(|path| (root, path)))
+    else {
+        return vec![];
+    };
+
+    // directories moved into inputs are reported without the files they contain
+    let files = if path.is_dir() {
+        collect_files(vec![path], options)
+    } else if path.is_file() {
+        vec![path]
+    } else {
+        vec![]
+    };
+
+    files
+        .into_iter()
+        .filter(|file| options.accepts(&root.input, file))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_written_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("a.jpg");
+        fs::write(&path, "optimized").unwrap();
+
+        let mut written = Written::default();
+        written.record(path.clone());
+        assert!(written.contains(&path));
+
+        fs::write(&path, "replaced by user").unwrap();
+        assert!(!written.contains(&path));
+        assert!(!written.contains(&dir.path().join("b.jpg")));
+    }
+
+    #[test]
+    fn resolve_event_paths() {
+        let root = Root {
+            input: PathBuf::from("photos"),
+            canonical: PathBuf::from("/home/user/photos"),
+        };
+
+        assert_eq!(
+            root.resolve(Path::new("/home/user/photos/sub/a.png")),
+            Some(PathBuf::from("photos/sub/a.png"))
+        );
+        assert_eq!(
+            root.resolve(Path::new("/home/user/photos")),
+            Some(PathBuf::from("photos"))
+        );
+        assert_eq!(root.resolve(Path::new("/home/user/other/a.png")), None);
+    }
+}
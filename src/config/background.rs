@@ -0,0 +1,196 @@
+use std::str::FromStr;
+
+use rgb::RGB8;
+
+/// Enum representing a background used to flatten transparent images.
+///
+/// When an image with alpha channel is encoded with a [`Codec`](super::Codec) that doesn't
+/// support transparency, transparent pixels are composited onto this background.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::{config::Background, rgb::RGB8};
+///
+/// let white = Background::default();
+/// let red = Background::from_str("#ff0000").unwrap();
+/// let checkerboard = Background::from_str("checkerboard").unwrap();
+///
+/// assert_eq!(white, Background::Color(RGB8::new(255, 255, 255)));
+/// assert_eq!(red, Background::Color(RGB8::new(255, 0, 0)));
+/// assert_eq!(checkerboard, Background::Checkerboard(8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Background {
+    /// Solid background color.
+    Color(RGB8),
+
+    /// Checkerboard pattern of light and dark squares with the specified square size in pixels.
+    Checkerboard(usize),
+}
+
+impl Background {
+    /// Light color of the checkerboard pattern.
+    pub const CHECKERBOARD_LIGHT: RGB8 = RGB8::new(255, 255, 255);
+
+    /// Dark color of the checkerboard pattern.
+    pub const CHECKERBOARD_DARK: RGB8 = RGB8::new(204, 204, 204);
+
+    /// Gets the background color at the specified pixel position.
+    ///
+    /// # Parameters
+    ///
+    /// - `x`: The horizontal pixel position.
+    /// - `y`: The vertical pixel position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::Background, rgb::RGB8};
+    ///
+    /// let background = Background::Checkerboard(8);
+    ///
+    /// assert_eq!(background.color_at(0, 0), Background::CHECKERBOARD_LIGHT);
+    /// assert_eq!(background.color_at(8, 0), Background::CHECKERBOARD_DARK);
+    /// ```
+    #[inline]
+    pub fn color_at(&self, x: usize, y: usize) -> RGB8 {
+        match *self {
+            Background::Color(color) => color,
+            Background::Checkerboard(size) => {
+                let size = size.max(1);
+
+                if (x / size + y / size) & 1 == 0 {
+                    Self::CHECKERBOARD_LIGHT
+                } else {
+                    Self::CHECKERBOARD_DARK
+                }
+            }
+        }
+    }
+}
+
+impl Default for Background {
+    /// Creates a default white [`Background`].
+    fn default() -> Self {
+        Self::Color(RGB8::new(255, 255, 255))
+    }
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let background = s.trim().to_lowercase();
+
+        if let Some(size) = background.strip_prefix("checkerboard") {
+            return match size.strip_prefix(':') {
+                None if size.is_empty() => Ok(Self::Checkerboard(8)),
+                Some(size) => match size.parse() {
+                    Ok(size) if size > 0 => Ok(Self::Checkerboard(size)),
+                    _ => Err(format!("{size} is not valid checkerboard size")),
+                },
+                None => Err(format!("{s} is not valid background")),
+            };
+        }
+
+        Ok(Self::Color(match background.as_str() {
+            "white" => RGB8::new(255, 255, 255),
+            "black" => RGB8::new(0, 0, 0),
+            "gray" | "grey" => RGB8::new(128, 128, 128),
+            "red" => RGB8::new(255, 0, 0),
+            "green" => RGB8::new(0, 255, 0),
+            "blue" => RGB8::new(0, 0, 255),
+            color => parse_hex_color(color).ok_or(format!("{s} is not valid background"))?,
+        }))
+    }
+}
+
+/// Parses `#rrggbb` and `#rgb` colors, the leading `#` is optional.
+fn parse_hex_color(s: &str) -> Option<RGB8> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize, len: usize| u8::from_str_radix(&hex[i * len..(i + 1) * len], 16).ok();
+
+    match hex.len() {
+        6 => Some(RGB8::new(channel(0, 2)?, channel(1, 2)?, channel(2, 2)?)),
+        3 => Some(RGB8::new(
+            channel(0, 1)? * 17,
+            channel(1, 1)? * 17,
+            channel(2, 1)? * 17,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_background() {
+        assert_eq!(
+            Background::default(),
+            Background::Color(RGB8::new(255, 255, 255))
+        );
+    }
+
+    #[test]
+    fn to_color() {
+        assert_eq!(
+            Background::from_str("#1a2B3c"),
+            Ok(Background::Color(RGB8::new(0x1a, 0x2b, 0x3c)))
+        );
+        assert_eq!(
+            Background::from_str("1a2b3c"),
+            Ok(Background::Color(RGB8::new(0x1a, 0x2b, 0x3c)))
+        );
+        assert_eq!(
+            Background::from_str("#fa0"),
+            Ok(Background::Color(RGB8::new(0xff, 0xaa, 0x00)))
+        );
+        assert_eq!(
+            Background::from_str("Black"),
+            Ok(Background::Color(RGB8::new(0, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn to_checkerboard() {
+        assert_eq!(
+            Background::from_str("checkerboard"),
+            Ok(Background::Checkerboard(8))
+        );
+        assert_eq!(
+            Background::from_str("checkerboard:16"),
+            Ok(Background::Checkerboard(16))
+        );
+        assert!(Background::from_str("checkerboard:0").is_err());
+        assert!(Background::from_str("checkerboard16").is_err());
+    }
+
+    #[test]
+    fn to_unknown() {
+        let background = Background::from_str("#12345");
+        assert!(background.is_err());
+        assert_eq!(background.unwrap_err(), "#12345 is not valid background");
+        assert!(Background::from_str("purple").is_err());
+        assert!(Background::from_str("#ggg").is_err());
+    }
+
+    #[test]
+    fn checkerboard_colors() {
+        let background = Background::Checkerboard(2);
+
+        assert_eq!(background.color_at(0, 0), Background::CHECKERBOARD_LIGHT);
+        assert_eq!(background.color_at(1, 1), Background::CHECKERBOARD_LIGHT);
+        assert_eq!(background.color_at(2, 0), Background::CHECKERBOARD_DARK);
+        assert_eq!(background.color_at(0, 2), Background::CHECKERBOARD_DARK);
+        assert_eq!(background.color_at(2, 2), Background::CHECKERBOARD_LIGHT);
+    }
+}
@@ -40,6 +40,18 @@ pub enum Codec {
     /// AVIF codec for image encoding and decoding.
     #[cfg(feature = "avif")]
     Avif,
+
+    /// TIFF codec with LZW, Deflate or no compression, preserves 16-bit images.
+    Tiff,
+
+    /// GIF codec, images are always quantized to 256 colors.
+    Gif,
+
+    /// QOI codec for fast lossless encoding and decoding.
+    Qoi,
+
+    /// BMP codec for uncompressed bitmaps.
+    Bmp,
 }
 
 impl Codec {
@@ -72,8 +84,61 @@ impl Codec {
             Codec::WebP => "webp",
             #[cfg(feature = "avif")]
             Codec::Avif => "avif",
+            Codec::Tiff => "tiff",
+            Codec::Gif => "gif",
+            Codec::Qoi => "qoi",
+            Codec::Bmp => "bmp",
+        }
+    }
+
+    /// Checks if the codec can store an alpha channel.
+    ///
+    /// Images with transparency are flattened onto the configured
+    /// [`Background`](super::Background) before encoding with codecs that can't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::Codec;
+    ///
+    /// assert!(!Codec::MozJpeg.supports_alpha());
+    /// assert!(Codec::Png.supports_alpha());
+    /// ```
+    pub fn supports_alpha(&self) -> bool {
+        match self {
+            Codec::MozJpeg => false,
+            Codec::Png => true,
+            #[cfg(feature = "jxl")]
+            Codec::JpegXl => true,
+            #[cfg(feature = "oxipng")]
+            Codec::OxiPng => true,
+            #[cfg(feature = "webp")]
+            Codec::WebP => true,
+            #[cfg(feature = "avif")]
+            Codec::Avif => true,
+            Codec::Tiff => true,
+            Codec::Gif => true,
+            Codec::Qoi => true,
+            Codec::Bmp => true,
         }
     }
+
+    /// Checks if the codec can store floating point HDR images.
+    ///
+    /// HDR images are tone mapped with the configured
+    /// [`ToneMappingConfig`](super::ToneMappingConfig) before encoding with codecs that can't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::Codec;
+    ///
+    /// assert!(!Codec::MozJpeg.supports_hdr());
+    /// assert!(Codec::Tiff.supports_hdr());
+    /// ```
+    pub fn supports_hdr(&self) -> bool {
+        matches!(self, Codec::Tiff)
+    }
 }
 
 impl FromStr for Codec {
@@ -91,6 +156,10 @@ impl FromStr for Codec {
             "webp" => Self::WebP,
             #[cfg(feature = "avif")]
             "avif" => Self::Avif,
+            "tiff" | "tif" => Self::Tiff,
+            "gif" => Self::Gif,
+            "qoi" => Self::Qoi,
+            "bmp" => Self::Bmp,
 
             codec => return Err(format!("{codec} is not supported codec.")),
         })
@@ -103,9 +172,9 @@ mod tests {
 
     #[test]
     fn to_unknown() {
-        let codec = Codec::from_str("bmp");
+        let codec = Codec::from_str("psd");
         assert!(codec.is_err());
-        assert_eq!(codec.unwrap_err(), "bmp is not supported codec.");
+        assert_eq!(codec.unwrap_err(), "psd is not supported codec.");
     }
 
     #[test]
@@ -155,6 +224,35 @@ mod tests {
         assert_eq!(codec.unwrap(), Codec::WebP);
     }
 
+    #[test]
+    fn to_legacy_codecs() {
+        assert_eq!(Codec::from_str("tiff"), Ok(Codec::Tiff));
+        assert_eq!(Codec::from_str("tif"), Ok(Codec::Tiff));
+        assert_eq!(Codec::from_str("gif"), Ok(Codec::Gif));
+        assert_eq!(Codec::from_str("qoi"), Ok(Codec::Qoi));
+        assert_eq!(Codec::from_str("BMP"), Ok(Codec::Bmp));
+
+        assert_eq!(Codec::Tiff.to_extension(), "tiff");
+        assert_eq!(Codec::Gif.to_extension(), "gif");
+        assert_eq!(Codec::Qoi.to_extension(), "qoi");
+        assert_eq!(Codec::Bmp.to_extension(), "bmp");
+    }
+
+    #[test]
+    fn alpha_support() {
+        assert!(!Codec::MozJpeg.supports_alpha());
+        assert!(Codec::Png.supports_alpha());
+        #[cfg(feature = "oxipng")]
+        assert!(Codec::OxiPng.supports_alpha());
+    }
+
+    #[test]
+    fn hdr_support() {
+        assert!(!Codec::MozJpeg.supports_hdr());
+        assert!(!Codec::Png.supports_hdr());
+        assert!(Codec::Tiff.supports_hdr());
+    }
+
     #[test]
     #[cfg(feature = "avif")]
     fn to_avif() {
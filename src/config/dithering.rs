@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+/// Enum representing the dithering method used when remapping an image onto a palette.
+///
+/// The strength of every method is controlled by the dithering level of
+/// [`QuantizationConfig`](super::QuantizationConfig).
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::config::Dithering;
+///
+/// assert_eq!(Dithering::from_str("atkinson"), Ok(Dithering::Atkinson));
+/// assert_eq!(Dithering::default(), Dithering::FloydSteinberg);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dithering {
+    /// Floyd–Steinberg error diffusion, performed by `imagequant`.
+    #[default]
+    FloydSteinberg,
+
+    /// Atkinson error diffusion, which diffuses only 3/4 of the error and keeps more contrast.
+    Atkinson,
+
+    /// Ordered dithering with an 8x8 Bayer matrix.
+    Ordered,
+
+    /// Ordered dithering with a blue noise threshold map.
+    BlueNoise,
+}
+
+impl FromStr for Dithering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "floyd-steinberg" | "fs" => Self::FloydSteinberg,
+            "atkinson" => Self::Atkinson,
+            "ordered" | "bayer" => Self::Ordered,
+            "blue-noise" => Self::BlueNoise,
+            method => return Err(format!("{method} is not valid dithering method")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_dithering() {
+        assert_eq!(
+            Dithering::from_str("floyd-steinberg"),
+            Ok(Dithering::FloydSteinberg)
+        );
+        assert_eq!(Dithering::from_str("fs"), Ok(Dithering::FloydSteinberg));
+        assert_eq!(Dithering::from_str("atkinson"), Ok(Dithering::Atkinson));
+        assert_eq!(Dithering::from_str("bayer"), Ok(Dithering::Ordered));
+        assert_eq!(Dithering::from_str("blue-noise"), Ok(Dithering::BlueNoise));
+    }
+
+    #[test]
+    fn to_unknown() {
+        let dithering = Dithering::from_str("random");
+        assert_eq!(
+            dithering.unwrap_err(),
+            "random is not valid dithering method"
+        );
+    }
+}
@@ -1,7 +1,12 @@
 use crate::error::InvalidEncoderConfig;
 
+use super::background::Background;
 use super::codec::Codec;
+use super::tiff_compression::TiffCompression;
+use super::tone_mapping_config::ToneMappingConfig;
 
+#[cfg(feature = "quantization")]
+use super::palette::Palette;
 #[cfg(feature = "quantization")]
 use super::quantization_config::QuantizationConfig;
 #[cfg(feature = "resizing")]
@@ -45,9 +50,22 @@ pub struct EncoderConfig {
     #[cfg(feature = "quantization")]
     quantization: Option<QuantizationConfig>,
 
+    /// Optional fixed palette the image is remapped onto during quantization.
+    #[cfg(feature = "quantization")]
+    palette: Option<Palette>,
+
     /// Optional resizing configuration for adjusting image dimensions.
     #[cfg(feature = "resizing")]
     resize: Option<ResizeConfig>,
+
+    /// The background used for flattening transparent images when the codec doesn't support alpha.
+    background: Background,
+
+    /// The compression used by the TIFF codec.
+    tiff_compression: TiffCompression,
+
+    /// The tone mapping applied to HDR images when the codec can't store them.
+    tone_mapping: ToneMappingConfig,
 }
 
 impl EncoderConfig {
@@ -71,8 +89,13 @@ impl EncoderConfig {
             codec,
             #[cfg(feature = "quantization")]
             quantization: None,
+            #[cfg(feature = "quantization")]
+            palette: None,
             #[cfg(feature = "resizing")]
             resize: None,
+            background: Background::default(),
+            tiff_compression: TiffCompression::default(),
+            tone_mapping: ToneMappingConfig::default(),
         }
     }
 
@@ -135,6 +158,35 @@ impl EncoderConfig {
         self
     }
 
+    /// Sets a fixed palette for image encoding.
+    ///
+    /// Instead of generating a palette for every image, the image is remapped onto the specified
+    /// palette. This enables quantization, if no [`QuantizationConfig`] is set the default one
+    /// is used for dithering.
+    ///
+    /// # Parameters
+    ///
+    /// - `palette`: A [`Palette`] shared between multiple images.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::{EncoderConfig, Codec, Palette}, rgb::RGBA8};
+    ///
+    /// let palette = Palette::new(vec![
+    ///     RGBA8::new(0, 0, 0, 255),
+    ///     RGBA8::new(255, 255, 255, 255),
+    /// ]).unwrap();
+    ///
+    /// let config = EncoderConfig::new(Codec::Png).with_palette(palette);
+    /// ```
+    #[inline]
+    #[cfg(feature = "quantization")]
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
     /// Sets the resizing configuration for image encoding.
     ///
     /// Resizing is an optional step that allows you to adjust the dimensions of the image before encoding.
@@ -163,6 +215,75 @@ impl EncoderConfig {
         self
     }
 
+    /// Sets the background for flattening transparent images.
+    ///
+    /// The background is used only when the image has an alpha channel and the configured
+    /// codec can't store it (see [`Codec::supports_alpha`]). Defaults to white.
+    ///
+    /// # Parameters
+    ///
+    /// - `background`: A [`Background`] used to composite transparent pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::{EncoderConfig, Codec, Background}, rgb::RGB8};
+    ///
+    /// let config = EncoderConfig::new(Codec::MozJpeg)
+    ///     .with_background(Background::Color(RGB8::new(0, 0, 0)));
+    /// ```
+    #[inline]
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Sets the compression used by the [`Codec::Tiff`] codec. Defaults to LZW.
+    ///
+    /// # Parameters
+    ///
+    /// - `compression`: A [`TiffCompression`] method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{EncoderConfig, Codec, TiffCompression};
+    ///
+    /// let config = EncoderConfig::new(Codec::Tiff)
+    ///     .with_tiff_compression(TiffCompression::Deflate);
+    /// ```
+    #[inline]
+    pub fn with_tiff_compression(mut self, compression: TiffCompression) -> Self {
+        self.tiff_compression = compression;
+        self
+    }
+
+    /// Sets the tone mapping applied to HDR images when the codec can't store them.
+    ///
+    /// Floating point images, like the ones decoded from HDR or OpenEXR files, are tone mapped
+    /// to 8-bit sRGB for every codec except [`Codec::Tiff`]. Defaults to [`ToneMapping::Aces`](super::ToneMapping::Aces).
+    ///
+    /// PQ and HLG images in JPEG XL and HEIF are decoded to linear floating point and tone mapped
+    /// the same way. AVIF images are decoded to 8-bit by libavif, so they are not tone mapped.
+    ///
+    /// # Parameters
+    ///
+    /// - `tone_mapping`: A [`ToneMappingConfig`] with the operator, exposure and peak luminance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{EncoderConfig, Codec, ToneMapping, ToneMappingConfig};
+    ///
+    /// let config = EncoderConfig::new(Codec::MozJpeg)
+    ///     .with_tone_mapping(ToneMappingConfig::new(ToneMapping::Hable));
+    /// ```
+    #[inline]
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMappingConfig) -> Self {
+        self.tone_mapping = tone_mapping;
+        self
+    }
+
     /// Gets the quality setting for image encoding.
     ///
     /// # Returns
@@ -230,6 +351,24 @@ impl EncoderConfig {
         self.quantization.as_ref()
     }
 
+    /// Gets the fixed palette for image encoding, if specified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::{EncoderConfig, Palette}, rgb::RGBA8};
+    ///
+    /// let palette = Palette::new(vec![RGBA8::new(0, 0, 0, 255); 2]).unwrap();
+    /// let config = EncoderConfig::default().with_palette(palette.clone());
+    ///
+    /// assert_eq!(config.palette(), Some(&palette));
+    /// ```
+    #[inline]
+    #[cfg(feature = "quantization")]
+    pub fn palette(&self) -> Option<&Palette> {
+        self.palette.as_ref()
+    }
+
     /// Gets the resize configuration for image encoding, if specified.
     ///
     /// # Returns
@@ -254,6 +393,58 @@ impl EncoderConfig {
     pub fn resize_config(&self) -> Option<&ResizeConfig> {
         self.resize.as_ref()
     }
+
+    /// Gets the background used for flattening transparent images.
+    ///
+    /// # Returns
+    ///
+    /// Returns a reference to the configured [`Background`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{EncoderConfig, Background};
+    ///
+    /// let config = EncoderConfig::default();
+    ///
+    /// assert_eq!(config.background(), &Background::default());
+    /// ```
+    #[inline]
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    /// Gets the compression used by the TIFF codec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{EncoderConfig, TiffCompression};
+    ///
+    /// let config = EncoderConfig::default();
+    ///
+    /// assert_eq!(config.tiff_compression(), TiffCompression::Lzw);
+    /// ```
+    #[inline]
+    pub fn tiff_compression(&self) -> TiffCompression {
+        self.tiff_compression
+    }
+
+    /// Gets the tone mapping applied to HDR images.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{EncoderConfig, ToneMapping};
+    ///
+    /// let config = EncoderConfig::default();
+    ///
+    /// assert_eq!(config.tone_mapping().operator(), ToneMapping::Aces);
+    /// ```
+    #[inline]
+    pub fn tone_mapping(&self) -> &ToneMappingConfig {
+        &self.tone_mapping
+    }
 }
 
 impl Default for EncoderConfig {
@@ -266,6 +457,7 @@ impl Default for EncoderConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ToneMapping;
 
     #[test]
     fn new_encoder_config() {
@@ -316,6 +508,16 @@ mod tests {
         assert_eq!(config.quantization_config(), Some(&quantization_config));
     }
 
+    #[test]
+    #[cfg(feature = "quantization")]
+    fn configure_palette() {
+        let palette = Palette::new(vec![rgb::RGBA8::new(0, 0, 0, 255); 2]).unwrap();
+
+        let config = EncoderConfig::new(Codec::Png).with_palette(palette.clone());
+
+        assert_eq!(config.palette(), Some(&palette));
+    }
+
     #[test]
     #[cfg(feature = "resizing")]
     fn configure_resize() {
@@ -326,6 +528,31 @@ mod tests {
         assert!(config.resize_config().is_some());
     }
 
+    #[test]
+    fn configure_background() {
+        let config =
+            EncoderConfig::new(Codec::MozJpeg).with_background(Background::Checkerboard(4));
+
+        assert_eq!(config.background(), &Background::Checkerboard(4));
+    }
+
+    #[test]
+    fn configure_tiff_compression() {
+        let config = EncoderConfig::new(Codec::Tiff).with_tiff_compression(TiffCompression::None);
+
+        assert_eq!(config.tiff_compression(), TiffCompression::None);
+    }
+
+    #[test]
+    fn configure_tone_mapping() {
+        let tone_mapping = ToneMappingConfig::new(ToneMapping::Reinhard)
+            .with_exposure(1.0)
+            .unwrap();
+        let config = EncoderConfig::default().with_tone_mapping(tone_mapping);
+
+        assert_eq!(config.tone_mapping(), &tone_mapping);
+    }
+
     #[test]
     fn default_encoder_config() {
         let config = EncoderConfig::default();
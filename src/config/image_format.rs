@@ -3,7 +3,7 @@ use std::{ffi::OsStr, path::Path};
 use crate::error::ImageFormatError;
 
 /// Enum representing supported image formats.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImageFormat {
     /// JPEG image format.
     Jpeg,
@@ -20,6 +20,33 @@ pub enum ImageFormat {
     /// AVIF image format.
     #[cfg(feature = "avif")]
     Avif,
+    /// GIF image format, only the first frame is decoded.
+    Gif,
+    /// BMP image format.
+    Bmp,
+    /// TGA image format.
+    Tga,
+    /// ICO image format, the largest image is decoded.
+    Ico,
+    /// QOI image format.
+    Qoi,
+    /// PNM image formats (PBM, PGM, PPM and PAM).
+    Pnm,
+    /// DDS image format.
+    Dds,
+    /// Radiance HDR image format.
+    Hdr,
+    /// OpenEXR image format.
+    OpenExr,
+    /// HEIF image format, including HEIC photos.
+    #[cfg(feature = "heif")]
+    Heif,
+    /// Camera raw formats (DNG, CR2, NEF, ARW, ORF, RW2, RAF and others), developed while decoding.
+    #[cfg(feature = "raw")]
+    Raw,
+    /// SVG vector image format, rasterized while decoding.
+    #[cfg(feature = "svg")]
+    Svg,
 }
 
 impl ImageFormat {
@@ -64,6 +91,23 @@ impl ImageFormat {
                 "webp" => Self::WebP,
                 #[cfg(feature = "avif")]
                 "avif" => Self::Avif,
+                "gif" => Self::Gif,
+                "bmp" | "dib" => Self::Bmp,
+                "tga" | "icb" | "vda" | "vst" => Self::Tga,
+                "ico" => Self::Ico,
+                "qoi" => Self::Qoi,
+                "pbm" | "pgm" | "ppm" | "pam" | "pnm" => Self::Pnm,
+                "dds" => Self::Dds,
+                "hdr" => Self::Hdr,
+                "exr" => Self::OpenExr,
+                #[cfg(feature = "heif")]
+                "heic" | "heif" | "hif" => Self::Heif,
+                #[cfg(feature = "raw")]
+                "dng" | "cr2" | "crw" | "nef" | "nrw" | "arw" | "srf" | "sr2" | "orf" | "rw2"
+                | "raf" | "pef" | "srw" | "3fr" | "kdc" | "dcr" | "dcs" | "mrw" | "erf" | "mef"
+                | "mos" | "iiq" | "rwl" | "ari" => Self::Raw,
+                #[cfg(feature = "svg")]
+                "svg" | "svgz" => Self::Svg,
                 ext => return Err(ImageFormatError::Unknown(ext.to_string())),
             },
         )
@@ -97,6 +141,175 @@ impl ImageFormat {
             .map(Self::from_ext)
             .ok_or(ImageFormatError::Missing)?
     }
+
+    /// Attempts to detect an [`ImageFormat`] from the leading bytes of an image.
+    ///
+    /// Recognizes JPEG, PNG, TIFF, GIF, BMP, ICO, QOI, PNM, DDS, Radiance HDR and OpenEXR
+    /// signatures, JPEG XL codestreams and containers, WebP RIFF headers, AVIF `ftyp` brands
+    /// SVG documents and camera raw formats with own signatures (ORF, RW2, RAF, CRW, MRW).
+    /// TGA, compressed SVG and TIFF based camera raw formats (DNG, CR2, NEF, ARW and others)
+    /// can only be detected from the file extension.
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The beginning of the image data, at least 32 bytes are recommended.
+    ///
+    /// # Returns
+    ///
+    /// Returns a [`Result`] with the detected [`ImageFormat`] on success or an [`ImageFormatError`] on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::ImageFormat;
+    ///
+    /// let bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    ///
+    /// assert_eq!(ImageFormat::from_bytes(bytes), Ok(ImageFormat::Png));
+    /// assert!(ImageFormat::from_bytes(b"plain text").is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ImageFormatError> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Ok(Self::Jpeg);
+        }
+
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return Ok(Self::Png);
+        }
+
+        if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+            return Ok(Self::Tiff);
+        }
+
+        if is_raw(bytes) {
+            #[cfg(feature = "raw")]
+            return Ok(Self::Raw);
+            #[cfg(not(feature = "raw"))]
+            return Err(ImageFormatError::Unknown("raw".to_string()));
+        }
+
+        if bytes.starts_with(&[0xFF, 0x0A]) || bytes.starts_with(b"\0\0\0\x0cJXL \r\n\x87\n") {
+            #[cfg(feature = "jxl")]
+            return Ok(Self::JpegXl);
+            #[cfg(not(feature = "jxl"))]
+            return Err(ImageFormatError::Unknown("jxl".to_string()));
+        }
+
+        if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            #[cfg(feature = "webp")]
+            return Ok(Self::WebP);
+            #[cfg(not(feature = "webp"))]
+            return Err(ImageFormatError::Unknown("webp".to_string()));
+        }
+
+        if bytes.len() >= 16 && &bytes[4..8] == b"ftyp" {
+            let size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+            let brands = &bytes[8..size.clamp(8, bytes.len())];
+
+            // major brand followed by minor version and compatible brands
+            let has_brand = |names: &[&[u8]]| {
+                brands
+                    .chunks_exact(4)
+                    .enumerate()
+                    .any(|(i, brand)| i != 1 && names.contains(&brand))
+            };
+
+            if has_brand(&[b"avif", b"avis"]) {
+                #[cfg(feature = "avif")]
+                return Ok(Self::Avif);
+                #[cfg(not(feature = "avif"))]
+                return Err(ImageFormatError::Unknown("avif".to_string()));
+            }
+
+            if has_brand(&[
+                b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"mif1", b"msf1",
+            ]) {
+                #[cfg(feature = "heif")]
+                return Ok(Self::Heif);
+                #[cfg(not(feature = "heif"))]
+                return Err(ImageFormatError::Unknown("heif".to_string()));
+            }
+        }
+
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            return Ok(Self::Gif);
+        }
+
+        if bytes.starts_with(b"BM") {
+            return Ok(Self::Bmp);
+        }
+
+        if bytes.len() >= 6 && bytes.starts_with(&[0, 0, 1, 0]) && bytes[4..6] != [0, 0] {
+            return Ok(Self::Ico);
+        }
+
+        if bytes.starts_with(b"qoif") {
+            return Ok(Self::Qoi);
+        }
+
+        if bytes.len() >= 3
+            && bytes[0] == b'P'
+            && (b'1'..=b'7').contains(&bytes[1])
+            && bytes[2].is_ascii_whitespace()
+        {
+            return Ok(Self::Pnm);
+        }
+
+        if bytes.starts_with(b"DDS ") {
+            return Ok(Self::Dds);
+        }
+
+        if bytes.starts_with(b"#?RADIANCE") || bytes.starts_with(b"#?RGBE") {
+            return Ok(Self::Hdr);
+        }
+
+        if bytes.starts_with(&[0x76, 0x2F, 0x31, 0x01]) {
+            return Ok(Self::OpenExr);
+        }
+
+        if is_svg(bytes) {
+            #[cfg(feature = "svg")]
+            return Ok(Self::Svg);
+            #[cfg(not(feature = "svg"))]
+            return Err(ImageFormatError::Unknown("svg".to_string()));
+        }
+
+        Err(ImageFormatError::Missing)
+    }
+}
+
+/// Checks if the bytes start with a signature of camera raw formats that aren't plain TIFF.
+fn is_raw(bytes: &[u8]) -> bool {
+    // Olympus ORF, Panasonic RW2, Fujifilm RAF and Minolta MRW
+    let signatures: [&[u8]; 6] = [
+        b"IIRO",
+        b"IIRS",
+        b"MMOR",
+        b"IIU\0",
+        b"FUJIFILMCCD-RAW",
+        b"\0MRM",
+    ];
+
+    // Canon CRW is a CIFF container
+    signatures
+        .iter()
+        .any(|signature| bytes.starts_with(signature))
+        || (bytes.len() >= 14 && &bytes[6..14] == b"HEAPCCDR")
+}
+
+/// Checks if the bytes start an XML document with an `<svg>` root element.
+fn is_svg(bytes: &[u8]) -> bool {
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace());
+    let text = &text[start.unwrap_or(text.len())..];
+
+    if text.starts_with(b"<svg") {
+        return true;
+    }
+
+    // the root element may follow an XML declaration, comments or a doctype
+    (text.starts_with(b"<?xml") || text.starts_with(b"<!"))
+        && text.windows(4).any(|window| window == b"<svg")
 }
 
 #[cfg(test)]
@@ -105,13 +318,202 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn from_bytes() {
+        assert_eq!(
+            ImageFormat::from_bytes(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 0x10]),
+            Ok(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            ImageFormat::from_bytes(b"\x89PNG\r\n\x1a\n"),
+            Ok(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::from_bytes(b"II*\0\x08\0"),
+            Ok(ImageFormat::Tiff)
+        );
+        assert_eq!(ImageFormat::from_bytes(b"MM\0*\0\0"), Ok(ImageFormat::Tiff));
+        assert_eq!(ImageFormat::from_bytes(b""), Err(ImageFormatError::Missing));
+        assert_eq!(
+            ImageFormat::from_bytes(b"plain text"),
+            Err(ImageFormatError::Missing)
+        );
+    }
+
+    #[test]
+    fn legacy_formats_from_bytes() {
+        let signatures: [(&[u8], ImageFormat); 9] = [
+            (b"GIF89a\x10\0", ImageFormat::Gif),
+            (b"BM\x36\x04\0\0", ImageFormat::Bmp),
+            (&[0, 0, 1, 0, 1, 0, 16, 16], ImageFormat::Ico),
+            (b"qoif\0\0\0\x10", ImageFormat::Qoi),
+            (b"P6\n16 16\n255\n", ImageFormat::Pnm),
+            (b"P7\nWIDTH 16\n", ImageFormat::Pnm),
+            (b"DDS |\0\0\0", ImageFormat::Dds),
+            (b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe", ImageFormat::Hdr),
+            (&[0x76, 0x2F, 0x31, 0x01, 2, 0, 0, 0], ImageFormat::OpenExr),
+        ];
+
+        for (bytes, format) in signatures {
+            assert_eq!(ImageFormat::from_bytes(bytes), Ok(format));
+        }
+
+        // Not a PNM without whitespace after the magic number
+        assert_eq!(
+            ImageFormat::from_bytes(b"Photo"),
+            Err(ImageFormatError::Missing)
+        );
+    }
+
+    #[test]
+    fn to_legacy_formats() {
+        let extensions = [
+            ("gif", ImageFormat::Gif),
+            ("BMP", ImageFormat::Bmp),
+            ("tga", ImageFormat::Tga),
+            ("ico", ImageFormat::Ico),
+            ("qoi", ImageFormat::Qoi),
+            ("ppm", ImageFormat::Pnm),
+            ("pgm", ImageFormat::Pnm),
+            ("dds", ImageFormat::Dds),
+            ("hdr", ImageFormat::Hdr),
+            ("exr", ImageFormat::OpenExr),
+        ];
+
+        for (ext, format) in extensions {
+            assert_eq!(ImageFormat::from_ext(ext), Ok(format));
+        }
+
+        let img_format = ImageFormat::from_path(&PathBuf::from("legacy/asset.tga"));
+        assert_eq!(img_format, Ok(ImageFormat::Tga));
+    }
+
+    #[test]
+    fn from_bytes_heif_is_not_avif() {
+        let heic = b"\0\0\0\x18ftypheic\0\0\0\0mif1heic";
+
+        #[cfg(feature = "heif")]
+        assert_eq!(ImageFormat::from_bytes(heic), Ok(ImageFormat::Heif));
+        #[cfg(not(feature = "heif"))]
+        assert_eq!(
+            ImageFormat::from_bytes(heic),
+            Err(ImageFormatError::Unknown("heif".to_string()))
+        );
+
+        // AVIF files list mif1 as well, but avif brand takes precedence
+        let avif = b"\0\0\0\x1cftypavif\0\0\0\0avifmif1miaf";
+        assert_ne!(ImageFormat::from_bytes(avif), ImageFormat::from_bytes(heic));
+    }
+
+    #[test]
+    #[cfg(feature = "heif")]
+    fn to_heif() {
+        for ext in ["heic", "HEIF", "hif"] {
+            assert_eq!(ImageFormat::from_ext(ext), Ok(ImageFormat::Heif));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "jxl")]
+    fn jxl_from_bytes() {
+        assert_eq!(
+            ImageFormat::from_bytes(&[0xFF, 0x0A, 0xFA, 0x1F]),
+            Ok(ImageFormat::JpegXl)
+        );
+        assert_eq!(
+            ImageFormat::from_bytes(b"\0\0\0\x0cJXL \r\n\x87\n\0\0\0\x14ftypjxl "),
+            Ok(ImageFormat::JpegXl)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "webp")]
+    fn webp_from_bytes() {
+        assert_eq!(
+            ImageFormat::from_bytes(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Ok(ImageFormat::WebP)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "avif")]
+    fn avif_from_bytes() {
+        assert_eq!(
+            ImageFormat::from_bytes(b"\0\0\0\x1cftypavif\0\0\0\0avifmif1miaf"),
+            Ok(ImageFormat::Avif)
+        );
+        assert_eq!(
+            ImageFormat::from_bytes(b"\0\0\0\x1cftypmif1\0\0\0\0mif1avismiaf"),
+            Ok(ImageFormat::Avif)
+        );
+    }
+
+    #[test]
+    fn svg_from_bytes() {
+        let documents: [&[u8]; 3] = [
+            b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>",
+            b"\xEF\xBB\xBF\n<?xml version=\"1.0\"?>\n<svg width=\"16\"/>",
+            b"<!-- icon -->\n<!DOCTYPE svg>\n<svg/>",
+        ];
+
+        for bytes in documents {
+            #[cfg(feature = "svg")]
+            assert_eq!(ImageFormat::from_bytes(bytes), Ok(ImageFormat::Svg));
+            #[cfg(not(feature = "svg"))]
+            assert_eq!(
+                ImageFormat::from_bytes(bytes),
+                Err(ImageFormatError::Unknown("svg".to_string()))
+            );
+        }
+
+        assert_eq!(
+            ImageFormat::from_bytes(b"<?xml version=\"1.0\"?><html/>"),
+            Err(ImageFormatError::Missing)
+        );
+    }
+
+    #[test]
+    fn raw_from_bytes() {
+        let signatures: [&[u8]; 4] = [
+            b"IIRO\x08\0\0\0",
+            b"IIU\0\x18\0\0\0",
+            b"FUJIFILMCCD-RAW 0201",
+            b"II\x1a\0\0\0HEAPCCDR",
+        ];
+
+        for bytes in signatures {
+            #[cfg(feature = "raw")]
+            assert_eq!(ImageFormat::from_bytes(bytes), Ok(ImageFormat::Raw));
+            #[cfg(not(feature = "raw"))]
+            assert_eq!(
+                ImageFormat::from_bytes(bytes),
+                Err(ImageFormatError::Unknown("raw".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "raw")]
+    fn to_raw() {
+        for ext in ["dng", "CR2", "nef", "arw", "orf", "rw2", "raf"] {
+            assert_eq!(ImageFormat::from_ext(ext), Ok(ImageFormat::Raw));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn to_svg() {
+        assert_eq!(ImageFormat::from_ext("svg"), Ok(ImageFormat::Svg));
+        assert_eq!(ImageFormat::from_ext("SVGZ"), Ok(ImageFormat::Svg));
+    }
+
     #[test]
     fn to_unknown() {
-        let img_format = ImageFormat::from_ext("bmp");
+        let img_format = ImageFormat::from_ext("psd");
         assert!(img_format.is_err());
         assert_eq!(
             img_format.unwrap_err(),
-            ImageFormatError::Unknown("bmp".to_string())
+            ImageFormatError::Unknown("psd".to_string())
         );
     }
 
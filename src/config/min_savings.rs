@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+/// Minimum size reduction an optimized image must reach to replace the original one.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::config::MinSavings;
+///
+/// let savings = MinSavings::from_str("10%").unwrap();
+///
+/// assert!(savings.is_met(1000, 900));
+/// assert!(!savings.is_met(1000, 950));
+/// assert_eq!(MinSavings::from_str("512"), Ok(MinSavings::Bytes(512)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MinSavings {
+    /// Optimized image must be smaller by at least this number of bytes.
+    Bytes(u64),
+
+    /// Optimized image must be smaller by at least this percentage of the original size.
+    Percent(f64),
+}
+
+impl MinSavings {
+    /// Checks whether the optimized image is small enough to replace the original one.
+    ///
+    /// # Parameters
+    ///
+    /// - `original_size`: The size of the original image in bytes.
+    /// - `optimized_size`: The size of the optimized image in bytes.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the optimized image is smaller by at least the minimum savings, images
+    /// larger than the original never are.
+    pub fn is_met(&self, original_size: u64, optimized_size: u64) -> bool {
+        let Some(saved) = original_size.checked_sub(optimized_size) else {
+            return false;
+        };
+
+        match *self {
+            Self::Bytes(bytes) => saved >= bytes,
+            Self::Percent(percent) => saved as f64 >= original_size as f64 * percent / 100.0,
+        }
+    }
+}
+
+impl Default for MinSavings {
+    /// Replaces the original image unless the optimized one is larger.
+    fn default() -> Self {
+        Self::Bytes(0)
+    }
+}
+
+impl FromStr for MinSavings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+
+        match value.strip_suffix('%') {
+            Some(percent) => match percent.trim().parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(Self::Percent(percent)),
+                _ => Err(format!("{s} is not valid percentage (0%-100%)")),
+            },
+            None => value
+                .parse()
+                .map(Self::Bytes)
+                .map_err(|_| format!("{s} is not valid number of bytes or percentage")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_min_savings() {
+        assert_eq!(MinSavings::from_str("1024"), Ok(MinSavings::Bytes(1024)));
+        assert_eq!(MinSavings::from_str("12.5%"), Ok(MinSavings::Percent(12.5)));
+        assert_eq!(MinSavings::from_str(" 5 %"), Ok(MinSavings::Percent(5.0)));
+    }
+
+    #[test]
+    fn to_invalid_min_savings() {
+        assert_eq!(
+            MinSavings::from_str("150%").unwrap_err(),
+            "150% is not valid percentage (0%-100%)"
+        );
+        assert_eq!(
+            MinSavings::from_str("-1").unwrap_err(),
+            "-1 is not valid number of bytes or percentage"
+        );
+    }
+
+    #[test]
+    fn compare_sizes() {
+        assert!(MinSavings::default().is_met(100, 100));
+        assert!(!MinSavings::default().is_met(100, 101));
+
+        assert!(MinSavings::Bytes(10).is_met(100, 90));
+        assert!(!MinSavings::Bytes(10).is_met(100, 91));
+
+        assert!(MinSavings::Percent(25.0).is_met(200, 150));
+        assert!(!MinSavings::Percent(25.0).is_met(200, 151));
+        assert!(!MinSavings::Percent(0.0).is_met(0, 1));
+    }
+}
@@ -1,7 +1,16 @@
+mod background;
 mod codec;
 mod encoder_config;
 mod image_format;
+mod min_savings;
+mod tiff_compression;
+mod tone_mapping;
+mod tone_mapping_config;
 
+#[cfg(feature = "quantization")]
+mod dithering;
+#[cfg(feature = "quantization")]
+mod palette;
 #[cfg(feature = "quantization")]
 mod quantization_config;
 #[cfg(feature = "resizing")]
@@ -9,10 +18,21 @@ mod resize_config;
 #[cfg(feature = "resizing")]
 mod resize_type;
 
+pub use background::Background;
 pub use codec::Codec;
 pub use encoder_config::EncoderConfig;
 pub use image_format::ImageFormat;
+pub use min_savings::MinSavings;
+pub use tiff_compression::TiffCompression;
+pub use tone_mapping::ToneMapping;
+pub use tone_mapping_config::ToneMappingConfig;
 
+#[cfg(feature = "quantization")]
+pub use dithering::Dithering;
+#[cfg(feature = "quantization")]
+pub(crate) use palette::quantize;
+#[cfg(feature = "quantization")]
+pub use palette::Palette;
 #[cfg(feature = "quantization")]
 pub use quantization_config::QuantizationConfig;
 #[cfg(feature = "resizing")]
@@ -0,0 +1,466 @@
+use std::{borrow::Borrow, str::FromStr};
+
+use image::DynamicImage;
+use rgb::{FromSlice, RGBA8};
+
+use crate::{error::InvalidQuantizationConfig, operations::dithering};
+
+use super::{dithering::Dithering, quantization_config::QuantizationConfig};
+
+/// A fixed color palette used to quantize images.
+///
+/// By default every image gets its own palette during quantization. A [`Palette`] allows
+/// remapping many images (e.g. sprite sheets, icon sets or animation frames) onto the
+/// same colors, so they look consistent with each other.
+///
+/// # Examples
+///
+/// Building a shared palette from multiple images:
+///
+/// ```
+/// use rimage::config::{Palette, QuantizationConfig};
+/// use image::{DynamicImage, RgbaImage};
+///
+/// let red = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255])));
+/// let blue = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, image::Rgba([0, 0, 255, 255])));
+///
+/// let palette = Palette::from_images([&red, &blue], &QuantizationConfig::default()).unwrap();
+///
+/// assert_eq!(palette.colors().len(), 2);
+/// ```
+///
+/// Creating a palette from known colors:
+///
+/// ```
+/// use rimage::{config::Palette, rgb::RGBA8};
+///
+/// let palette = Palette::new(vec![
+///     RGBA8::new(0, 0, 0, 255),
+///     RGBA8::new(255, 255, 255, 255),
+/// ]).unwrap();
+/// ```
+///
+/// Parsing a GIMP palette (`.gpl`) or a list of hex colors (`.hex`):
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::config::Palette;
+///
+/// let gpl = Palette::from_str("GIMP Palette\nName: Grays\n#\n  0   0   0\tBlack\n255 255 255\tWhite\n").unwrap();
+/// let hex = Palette::from_str("000000\nffffff\n").unwrap();
+///
+/// assert_eq!(gpl, hex);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// The colors of the palette, from 2 to 256 entries.
+    colors: Vec<RGBA8>,
+}
+
+impl Palette {
+    /// Creates a new [`Palette`] from the specified colors.
+    ///
+    /// # Parameters
+    ///
+    /// - `colors`: The palette colors, from 2 to 256 entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if the number of colors is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::Palette, rgb::RGBA8};
+    ///
+    /// let palette = Palette::new(vec![RGBA8::new(0, 0, 0, 255); 4]).unwrap();
+    ///
+    /// assert!(Palette::new(vec![RGBA8::new(0, 0, 0, 255)]).is_err());
+    /// ```
+    pub fn new(colors: Vec<RGBA8>) -> Result<Self, InvalidQuantizationConfig> {
+        if !(2..=256).contains(&colors.len()) {
+            return Err(InvalidQuantizationConfig::PaletteSizeOutOfBounds(
+                colors.len(),
+            ));
+        }
+
+        Ok(Self { colors })
+    }
+
+    /// Builds a single [`Palette`] for all specified images.
+    ///
+    /// Colors of every image are collected into one `imagequant` histogram, so the
+    /// resulting palette represents the whole set. Max colors, quality, speed, posterization
+    /// and gamma are taken from the quantization configuration. Palettes of single color images
+    /// repeat that color, as every palette has at least 2 entries.
+    ///
+    /// # Parameters
+    ///
+    /// - `images`: The images to build the palette from.
+    /// - `quantization_config`: The configuration used to generate the palette.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`imagequant::Error`] if no images were provided or quantization fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{Palette, QuantizationConfig};
+    /// use image::{DynamicImage, RgbImage};
+    ///
+    /// let images = (0..4).map(|i| {
+    ///     DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| image::Rgb([(x * 16) as u8, (y * 16) as u8, i * 64])))
+    /// });
+    ///
+    /// let config = QuantizationConfig::new().with_max_colors(16).unwrap();
+    /// let palette = Palette::from_images(images, &config).unwrap();
+    ///
+    /// assert!(palette.colors().len() <= 16);
+    /// ```
+    pub fn from_images<I>(
+        images: impl IntoIterator<Item = I>,
+        quantization_config: &QuantizationConfig,
+    ) -> Result<Self, imagequant::Error>
+    where
+        I: Borrow<DynamicImage>,
+    {
+        let liq = quantization_config.attributes()?;
+        let mut histogram = imagequant::Histogram::new(&liq);
+
+        for image in images {
+            let image = image.borrow().to_rgba8();
+
+            let mut img = liq.new_image_borrowed(
+                image.as_raw().as_rgba(),
+                image.width() as usize,
+                image.height() as usize,
+                quantization_config.gamma(),
+            )?;
+
+            histogram.add_image(&liq, &mut img)?;
+        }
+
+        let mut res = histogram.quantize(&liq)?;
+        let mut colors = res.palette_vec();
+
+        // images with a single color give a single color palette, which can't be indexed
+        while colors.len() < 2 {
+            colors.push(colors.last().copied().unwrap_or_default());
+        }
+
+        Ok(Self { colors })
+    }
+
+    /// Gets the colors of the palette.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::Palette, rgb::RGBA8};
+    ///
+    /// let colors = vec![RGBA8::new(0, 0, 0, 255), RGBA8::new(255, 255, 255, 255)];
+    /// let palette = Palette::new(colors.clone()).unwrap();
+    ///
+    /// assert_eq!(palette.colors(), &colors);
+    /// ```
+    #[inline]
+    pub fn colors(&self) -> &[RGBA8] {
+        &self.colors
+    }
+
+    /// Remaps pixels onto the palette, returning the used palette and per-pixel palette indices.
+    pub(crate) fn remap(
+        &self,
+        pixels: &[RGBA8],
+        width: usize,
+        height: usize,
+        quantization_config: &QuantizationConfig,
+    ) -> Result<(Vec<RGBA8>, Vec<u8>), imagequant::Error> {
+        if quantization_config.dithering_method() != Dithering::FloydSteinberg {
+            let indices = dithering::remap(
+                pixels,
+                width,
+                &self.colors,
+                quantization_config.dithering_method(),
+                quantization_config.dithering_level(),
+            );
+
+            return Ok((self.colors.clone(), indices));
+        }
+
+        // quality and posterization of the config would change or reject the fixed colors
+        let mut liq = imagequant::new();
+        liq.set_max_colors(self.colors.len() as u32)?;
+
+        let mut histogram = imagequant::Histogram::new(&liq);
+
+        for color in &self.colors {
+            histogram.add_fixed_color(*color, quantization_config.gamma())?;
+        }
+
+        let mut res = histogram.quantize(&liq)?;
+        res.set_dithering_level(quantization_config.dithering_level())?;
+
+        let mut img = liq.new_image_borrowed(pixels, width, height, quantization_config.gamma())?;
+
+        res.remapped(&mut img)
+    }
+}
+
+/// Quantizes pixels with `imagequant`, returning the palette and per-pixel palette indices.
+pub(crate) fn quantize(
+    pixels: &[RGBA8],
+    width: usize,
+    height: usize,
+    quantization_config: &QuantizationConfig,
+) -> Result<(Vec<RGBA8>, Vec<u8>), imagequant::Error> {
+    let liq = quantization_config.attributes()?;
+
+    let mut img = liq.new_image_borrowed(pixels, width, height, quantization_config.gamma())?;
+
+    let mut res = liq.quantize(&mut img)?;
+
+    if quantization_config.dithering_method() != Dithering::FloydSteinberg {
+        let palette = res.palette_vec();
+
+        let indices = dithering::remap(
+            pixels,
+            width,
+            &palette,
+            quantization_config.dithering_method(),
+            quantization_config.dithering_level(),
+        );
+
+        return Ok((palette, indices));
+    }
+
+    res.set_dithering_level(quantization_config.dithering_level())?;
+
+    res.remapped(&mut img)
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    /// Parses a GIMP palette (`.gpl`) or a list of `rrggbb`/`rrggbbaa` hex colors, one per line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).enumerate();
+
+        let gpl = s.trim_start().starts_with("GIMP Palette");
+
+        if gpl {
+            lines.next();
+        }
+
+        let mut colors = vec![];
+
+        for (i, line) in lines {
+            if line.is_empty() || gpl && line.starts_with('#') {
+                continue;
+            }
+
+            // header fields precede the colors, whose names may contain anything
+            if gpl
+                && colors.is_empty()
+                && (line.starts_with("Name:") || line.starts_with("Columns:"))
+            {
+                continue;
+            }
+
+            let color = if gpl {
+                parse_gpl_color(line)
+            } else {
+                parse_hex_color(line)
+            };
+
+            colors.push(color.ok_or(format!("{line} is not valid color on line {}", i + 1))?);
+        }
+
+        Self::new(colors).map_err(|e| e.to_string())
+    }
+}
+
+/// Parses `R G B [name]` line of a GIMP palette.
+fn parse_gpl_color(line: &str) -> Option<RGBA8> {
+    let mut channels = line.split_whitespace().map(|c| c.parse::<u8>().ok());
+
+    Some(RGBA8::new(
+        channels.next()??,
+        channels.next()??,
+        channels.next()??,
+        255,
+    ))
+}
+
+/// Parses `rrggbb` and `rrggbbaa` colors, the leading `#` is optional.
+fn parse_hex_color(line: &str) -> Option<RGBA8> {
+    let hex = line.strip_prefix('#').unwrap_or(line);
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok();
+
+    match hex.len() {
+        6 => Some(RGBA8::new(channel(0)?, channel(1)?, channel(2)?, 255)),
+        8 => Some(RGBA8::new(
+            channel(0)?,
+            channel(1)?,
+            channel(2)?,
+            channel(3)?,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_size() {
+        assert!(Palette::new(vec![RGBA8::default(); 2]).is_ok());
+        assert!(Palette::new(vec![RGBA8::default(); 256]).is_ok());
+
+        let result = Palette::new(vec![RGBA8::default(); 257]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Palette size 257 is out of bounds (2-256)."
+        );
+    }
+
+    #[test]
+    fn shared_palette() {
+        let images = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]].map(|color| {
+            DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(4, 4, image::Rgba(color)))
+        });
+
+        let palette = Palette::from_images(&images, &QuantizationConfig::default()).unwrap();
+
+        assert_eq!(palette.colors().len(), 3);
+
+        for color in [
+            RGBA8::new(255, 0, 0, 255),
+            RGBA8::new(0, 255, 0, 255),
+            RGBA8::new(0, 0, 255, 255),
+        ] {
+            assert!(palette.colors().contains(&color));
+        }
+    }
+
+    #[test]
+    fn single_color_palette() {
+        let red = RGBA8::new(255, 0, 0, 255);
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            4,
+            4,
+            image::Rgba([255, 0, 0, 255]),
+        ));
+
+        let palette = Palette::from_images([image], &QuantizationConfig::default()).unwrap();
+
+        assert_eq!(palette.colors(), &[red, red]);
+    }
+
+    #[test]
+    fn remap_to_palette() {
+        let black = RGBA8::new(0, 0, 0, 255);
+        let white = RGBA8::new(255, 255, 255, 255);
+
+        let palette = Palette::new(vec![black, white]).unwrap();
+        let config = QuantizationConfig::new().with_dithering(0.0).unwrap();
+
+        let pixels = [
+            RGBA8::new(10, 10, 10, 255),
+            RGBA8::new(240, 240, 240, 255),
+            RGBA8::new(30, 20, 10, 255),
+            RGBA8::new(200, 220, 250, 255),
+        ];
+
+        let (colors, indices) = palette.remap(&pixels, 2, 2, &config).unwrap();
+
+        let remapped: Vec<RGBA8> = indices.iter().map(|i| colors[*i as usize]).collect();
+
+        assert_eq!(remapped, vec![black, white, black, white]);
+    }
+
+    #[test]
+    fn remap_keeps_fixed_colors() {
+        let dark = RGBA8::new(1, 3, 5, 255);
+        let light = RGBA8::new(250, 251, 253, 255);
+
+        let palette = Palette::new(vec![dark, light]).unwrap();
+        let config = QuantizationConfig::new()
+            .with_posterization(4)
+            .unwrap()
+            .with_quality_range(99, 100)
+            .unwrap();
+
+        let pixels = vec![RGBA8::new(128, 64, 32, 255); 4 * 4];
+
+        let (colors, _) = palette.remap(&pixels, 4, 4, &config).unwrap();
+
+        assert!(colors.iter().all(|color| *color == dark || *color == light));
+    }
+
+    #[test]
+    fn remap_with_dithering_method() {
+        let black = RGBA8::new(0, 0, 0, 255);
+        let white = RGBA8::new(255, 255, 255, 255);
+
+        let palette = Palette::new(vec![black, white]).unwrap();
+        let config = QuantizationConfig::new().with_dithering_method(Dithering::Ordered);
+
+        let pixels = vec![RGBA8::new(128, 128, 128, 255); 8 * 8];
+
+        let (colors, indices) = palette.remap(&pixels, 8, 8, &config).unwrap();
+
+        assert_eq!(colors, vec![black, white]);
+        assert_eq!(indices.iter().filter(|i| **i == 1).count(), 32);
+    }
+
+    #[test]
+    fn parse_gpl() {
+        let palette = Palette::from_str(
+            "GIMP Palette\nName: Brand\nColumns: 2\n# comment\n255   0   0\tRed\n  0  64 128\n 10  20  30\tText: dark\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            palette.colors(),
+            &[
+                RGBA8::new(255, 0, 0, 255),
+                RGBA8::new(0, 64, 128, 255),
+                RGBA8::new(10, 20, 30, 255)
+            ]
+        );
+
+        assert_eq!(
+            Palette::from_str("GIMP Palette\n255 0 0\nName: Late\n").unwrap_err(),
+            "Name: Late is not valid color on line 3"
+        );
+
+        assert_eq!(
+            Palette::from_str("GIMP Palette\n255 0 0\n0 0\n").unwrap_err(),
+            "0 0 is not valid color on line 3"
+        );
+    }
+
+    #[test]
+    fn parse_hex() {
+        let palette = Palette::from_str("#ff0000\n\n00408080\n").unwrap();
+
+        assert_eq!(
+            palette.colors(),
+            &[RGBA8::new(255, 0, 0, 255), RGBA8::new(0, 64, 128, 128)]
+        );
+
+        assert!(Palette::from_str("ff0000\nblue\n").is_err());
+        assert_eq!(
+            Palette::from_str("ff0000\n").unwrap_err(),
+            "Palette size 1 is out of bounds (2-256)."
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use crate::error::InvalidQuantizationConfig;
 
+use super::dithering::Dithering;
+
 /// Configuration struct for image quantization.
 ///
 /// The [`QuantizationConfig`] struct allows you to configure settings related to image quantization,
 /// which is a process used in image compression. It includes parameters for controlling the quality
-/// of quantization and the level of dithering applied to the image during the process.
+/// of quantization and the level of dithering applied to the image during the process, as well as
+/// the palette size, speed, posterization and input gamma passed to `imagequant`.
 ///
 /// # Examples
 ///
@@ -25,13 +28,42 @@ use crate::error::InvalidQuantizationConfig;
 ///     .with_quality(90).unwrap()
 ///     .with_dithering(0.75).unwrap();
 /// ```
+///
+/// Creating a [`QuantizationConfig`] matching `pngquant --quality 65-80 --speed 1 64`:
+///
+/// ```
+/// use rimage::config::QuantizationConfig;
+///
+/// let config = QuantizationConfig::new()
+///     .with_quality_range(65, 80).unwrap()
+///     .with_speed(1).unwrap()
+///     .with_max_colors(64).unwrap();
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct QuantizationConfig {
-    /// The quality level for image quantization, ranging from 0 to 100.
+    /// The target (maximum) quality level for image quantization, ranging from 0 to 100.
     quality: u8,
 
+    /// The minimum quality level for image quantization, ranging from 0 to 100.
+    min_quality: u8,
+
     /// The level of dithering applied during quantization, ranging from 0.0 to 1.0.
     dithering_level: f32,
+
+    /// The dithering method used when remapping the image onto the palette.
+    dithering_method: Dithering,
+
+    /// The maximum number of colors in the palette, ranging from 2 to 256.
+    max_colors: u32,
+
+    /// The speed/quality trade-off, ranging from 1 (slowest) to 10 (fastest).
+    speed: u8,
+
+    /// The number of least significant bits to ignore, ranging from 0 to 4.
+    posterization: u8,
+
+    /// The gamma of the input image, ranging from 0.0 to 1.0, where 0.0 means sRGB.
+    gamma: f64,
 }
 
 impl QuantizationConfig {
@@ -60,7 +92,8 @@ impl QuantizationConfig {
     ///
     /// # Errors
     ///
-    /// Returns an [`InvalidQuantizationConfig`] error if the quality level is out of bounds (not in the range 0-100).
+    /// Returns an [`InvalidQuantizationConfig`] error if the quality level is out of bounds (not in the range 0-100)
+    /// or lower than the configured minimum quality.
     ///
     /// # Examples
     ///
@@ -69,12 +102,162 @@ impl QuantizationConfig {
     ///
     /// let config = QuantizationConfig::new().with_quality(90).unwrap();
     /// ```
-    pub fn with_quality(mut self, quality: u8) -> Result<Self, InvalidQuantizationConfig> {
-        if quality > 100 {
-            return Err(InvalidQuantizationConfig::QualityOutOfBounds(quality));
+    pub fn with_quality(self, quality: u8) -> Result<Self, InvalidQuantizationConfig> {
+        self.with_quality_range(self.min_quality, quality)
+    }
+
+    /// Sets the minimum and target quality levels for image quantization.
+    ///
+    /// Quantization aims for `max` quality using as few colors as possible. If even
+    /// the full palette can't reach `min` quality, encoding fails with an error recognized
+    /// by [`is_quality_too_low`](crate::error::is_quality_too_low), so the image can be skipped.
+    ///
+    /// # Parameters
+    ///
+    /// - `min`: The minimum acceptable quality, ranging from 0 to 100.
+    /// - `max`: The target quality, ranging from 0 to 100.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if any of the values is out of bounds
+    /// or `min` is greater than `max`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::new().with_quality_range(65, 80).unwrap();
+    ///
+    /// assert_eq!(config.min_quality(), 65);
+    /// assert_eq!(config.quality(), 80);
+    /// ```
+    pub fn with_quality_range(
+        mut self,
+        min: u8,
+        max: u8,
+    ) -> Result<Self, InvalidQuantizationConfig> {
+        if min > 100 {
+            return Err(InvalidQuantizationConfig::QualityOutOfBounds(min));
+        }
+
+        if max > 100 {
+            return Err(InvalidQuantizationConfig::QualityOutOfBounds(max));
+        }
+
+        if min > max {
+            return Err(InvalidQuantizationConfig::InvalidQualityRange(min, max));
+        }
+
+        self.min_quality = min;
+        self.quality = max;
+        Ok(self)
+    }
+
+    /// Sets the maximum number of colors in the palette.
+    ///
+    /// # Parameters
+    ///
+    /// - `max_colors`: The maximum palette size, ranging from 2 to 256.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if the number of colors is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::new().with_max_colors(16).unwrap();
+    /// ```
+    pub fn with_max_colors(mut self, max_colors: u32) -> Result<Self, InvalidQuantizationConfig> {
+        if !(2..=256).contains(&max_colors) {
+            return Err(InvalidQuantizationConfig::MaxColorsOutOfBounds(max_colors));
+        }
+
+        self.max_colors = max_colors;
+        Ok(self)
+    }
+
+    /// Sets the speed/quality trade-off of quantization.
+    ///
+    /// # Parameters
+    ///
+    /// - `speed`: The speed, ranging from 1 (slowest, best quality) to 10 (fastest).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if the speed is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::new().with_speed(1).unwrap();
+    /// ```
+    pub fn with_speed(mut self, speed: u8) -> Result<Self, InvalidQuantizationConfig> {
+        if !(1..=10).contains(&speed) {
+            return Err(InvalidQuantizationConfig::SpeedOutOfBounds(speed));
         }
 
-        self.quality = quality;
+        self.speed = speed;
+        Ok(self)
+    }
+
+    /// Sets the number of least significant bits ignored in every color channel.
+    ///
+    /// Posterization reduces precision of the palette, which is useful for displays
+    /// with lower color depth, e.g. 4 bits for ARGB4444 textures.
+    ///
+    /// # Parameters
+    ///
+    /// - `bits`: The number of ignored bits, ranging from 0 to 4.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if the number of bits is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::new().with_posterization(2).unwrap();
+    /// ```
+    pub fn with_posterization(mut self, bits: u8) -> Result<Self, InvalidQuantizationConfig> {
+        if bits > 4 {
+            return Err(InvalidQuantizationConfig::PosterizationOutOfBounds(bits));
+        }
+
+        self.posterization = bits;
+        Ok(self)
+    }
+
+    /// Sets the gamma of the input image.
+    ///
+    /// # Parameters
+    ///
+    /// - `gamma`: The input gamma, ranging from 0.0 to 1.0, where 0.0 means the default sRGB gamma (1/2.2).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidQuantizationConfig`] error if the gamma is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::new().with_gamma(1.0 / 1.8).unwrap();
+    /// ```
+    pub fn with_gamma(mut self, gamma: f64) -> Result<Self, InvalidQuantizationConfig> {
+        if !(0.0..=1.0).contains(&gamma) {
+            return Err(InvalidQuantizationConfig::GammaOutOfBounds(gamma));
+        }
+
+        self.gamma = gamma;
         Ok(self)
     }
 
@@ -108,6 +291,24 @@ impl QuantizationConfig {
         Ok(self)
     }
 
+    /// Sets the dithering method used when remapping the image onto the palette.
+    ///
+    /// # Parameters
+    ///
+    /// - `method`: The [`Dithering`] method, the strength is controlled by the dithering level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{Dithering, QuantizationConfig};
+    ///
+    /// let config = QuantizationConfig::new().with_dithering_method(Dithering::Ordered);
+    /// ```
+    pub fn with_dithering_method(mut self, method: Dithering) -> Self {
+        self.dithering_method = method;
+        self
+    }
+
     /// Gets the quality setting for quantization.
     ///
     /// # Returns
@@ -128,6 +329,97 @@ impl QuantizationConfig {
         self.quality
     }
 
+    /// Gets the minimum quality setting for quantization.
+    ///
+    /// # Returns
+    ///
+    /// Returns the minimum quality level as a unsigned byte value in the range [0, 100].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.min_quality(), 0);
+    /// ```
+    pub fn min_quality(&self) -> u8 {
+        self.min_quality
+    }
+
+    /// Gets the maximum number of colors in the palette.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.max_colors(), 256);
+    /// ```
+    pub fn max_colors(&self) -> u32 {
+        self.max_colors
+    }
+
+    /// Gets the speed setting for quantization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.speed(), 4);
+    /// ```
+    pub fn speed(&self) -> u8 {
+        self.speed
+    }
+
+    /// Gets the number of posterization bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.posterization(), 0);
+    /// ```
+    pub fn posterization(&self) -> u8 {
+        self.posterization
+    }
+
+    /// Gets the gamma of the input image, 0.0 means sRGB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::QuantizationConfig;
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.gamma(), 0.0);
+    /// ```
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    /// Creates `imagequant` attributes from this configuration.
+    pub(crate) fn attributes(&self) -> Result<imagequant::Attributes, imagequant::Error> {
+        let mut liq = imagequant::new();
+
+        liq.set_speed(self.speed as i32)?;
+        liq.set_quality(self.min_quality, self.quality)?;
+        liq.set_max_colors(self.max_colors)?;
+        liq.set_min_posterization(self.posterization)?;
+
+        Ok(liq)
+    }
+
     /// Gets the dithering level for quantization.
     ///
     /// # Returns
@@ -147,14 +439,38 @@ impl QuantizationConfig {
     pub fn dithering_level(&self) -> f32 {
         self.dithering_level
     }
+
+    /// Gets the dithering method for quantization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{Dithering, QuantizationConfig};
+    ///
+    /// let config = QuantizationConfig::default();
+    ///
+    /// assert_eq!(config.dithering_method(), Dithering::FloydSteinberg);
+    /// ```
+    pub fn dithering_method(&self) -> Dithering {
+        self.dithering_method
+    }
 }
 
 impl Default for QuantizationConfig {
     /// Creates a default [`QuantizationConfig`] with a quality of 100 and a dithering level of 1.0.
+    ///
+    /// Other settings match `imagequant` defaults: no minimum quality, 256 colors, speed 4,
+    /// no posterization and sRGB gamma.
     fn default() -> Self {
         Self {
             quality: 100,
+            min_quality: 0,
             dithering_level: 1.0,
+            dithering_method: Dithering::FloydSteinberg,
+            max_colors: 256,
+            speed: 4,
+            posterization: 0,
+            gamma: 0.0,
         }
     }
 }
@@ -209,12 +525,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn configure_quality_range() {
+        let config = QuantizationConfig::new()
+            .with_quality_range(60, 80)
+            .unwrap();
+        assert_eq!(config.min_quality(), 60);
+        assert_eq!(config.quality(), 80);
+
+        // Test maximum quality lower than minimum
+        let result = config.with_quality(50);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Minimum quality 60 is greater than maximum quality 50."
+        );
+
+        let result = QuantizationConfig::new().with_quality_range(110, 120);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Quality value 110 is out of bounds (0-100)."
+        );
+    }
+
+    #[test]
+    fn configure_max_colors() {
+        let config = QuantizationConfig::new().with_max_colors(2).unwrap();
+        assert_eq!(config.max_colors(), 2);
+
+        let result = QuantizationConfig::new().with_max_colors(1);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Max colors value 1 is out of bounds (2-256)."
+        );
+        assert!(QuantizationConfig::new().with_max_colors(257).is_err());
+    }
+
+    #[test]
+    fn configure_speed() {
+        let config = QuantizationConfig::new().with_speed(10).unwrap();
+        assert_eq!(config.speed(), 10);
+
+        let result = QuantizationConfig::new().with_speed(0);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Speed value 0 is out of bounds (1-10)."
+        );
+    }
+
+    #[test]
+    fn configure_posterization() {
+        let config = QuantizationConfig::new().with_posterization(4).unwrap();
+        assert_eq!(config.posterization(), 4);
+
+        let result = QuantizationConfig::new().with_posterization(5);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Posterization value 5 is out of bounds (0-4)."
+        );
+    }
+
+    #[test]
+    fn configure_gamma() {
+        let config = QuantizationConfig::new().with_gamma(0.5).unwrap();
+        assert_eq!(config.gamma(), 0.5);
+
+        let result = QuantizationConfig::new().with_gamma(2.2);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Gamma value 2.2 is out of bounds (0.0-1.0)."
+        );
+    }
+
     #[test]
     fn default_quantization_config() {
         // Test the default QuantizationConfig
         let config = QuantizationConfig::default();
 
         assert_eq!(config.quality(), 100);
+        assert_eq!(config.min_quality(), 0);
         assert_eq!(config.dithering_level(), 1.0);
+        assert_eq!(config.max_colors(), 256);
+        assert_eq!(config.speed(), 4);
+        assert_eq!(config.posterization(), 0);
+        assert_eq!(config.gamma(), 0.0);
+        assert_eq!(config.dithering_method(), Dithering::FloydSteinberg);
+    }
+
+    #[test]
+    fn configure_dithering_method() {
+        let config = QuantizationConfig::new().with_dithering_method(Dithering::BlueNoise);
+        assert_eq!(config.dithering_method(), Dithering::BlueNoise);
     }
 }
@@ -164,6 +164,46 @@ impl ResizeConfig {
     pub fn filter_type(&self) -> ResizeType {
         self.filter_type
     }
+
+    /// Calculates the size of a `width` x `height` image after resizing.
+    ///
+    /// A missing dimension is derived from the aspect ratio, and the result is fitted into
+    /// the target size the same way [`DynamicImage::resize`](image::DynamicImage::resize) does,
+    /// so resizing an image that already has the returned size leaves it unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{ResizeConfig, ResizeType};
+    ///
+    /// let config = ResizeConfig::new(ResizeType::Lanczos3).with_width(800);
+    ///
+    /// assert_eq!(config.dimensions(1600, 1200), (800, 600));
+    /// ```
+    pub fn dimensions(&self, width: u32, height: u32) -> (u32, u32) {
+        let (width, height) = (width.max(1) as u64, height.max(1) as u64);
+
+        let target_width = self
+            .width
+            .map(|w| w as u64)
+            .or(self.height.map(|h| h as u64 * width / height))
+            .unwrap_or(width);
+        let target_height = self
+            .height
+            .map(|h| h as u64)
+            .or(self.width.map(|w| w as u64 * height / width))
+            .unwrap_or(height);
+
+        let ratio = f64::min(
+            target_width as f64 / width as f64,
+            target_height as f64 / height as f64,
+        );
+
+        (
+            ((width as f64 * ratio).round() as u32).max(1),
+            ((height as f64 * ratio).round() as u32).max(1),
+        )
+    }
 }
 
 impl Default for ResizeConfig {
@@ -208,4 +248,25 @@ mod tests {
         assert_eq!(resize_config.height(), Some(120));
         assert_eq!(resize_config.filter_type(), ResizeType::Lanczos3);
     }
+
+    #[test]
+    fn dimensions() {
+        let resize_config = ResizeConfig::default();
+        assert_eq!(resize_config.dimensions(800, 600), (800, 600));
+
+        let resize_config = ResizeConfig::default().with_width(400);
+        assert_eq!(resize_config.dimensions(800, 600), (400, 300));
+
+        // derived height is rounded down and the width is fitted to it
+        let resize_config = ResizeConfig::default().with_width(333);
+        assert_eq!(resize_config.dimensions(800, 600), (332, 249));
+        assert_eq!(resize_config.dimensions(332, 249), (332, 249));
+
+        let resize_config = ResizeConfig::default().with_height(300);
+        assert_eq!(resize_config.dimensions(800, 600), (400, 300));
+
+        let resize_config = ResizeConfig::default().with_width(400).with_height(400);
+        assert_eq!(resize_config.dimensions(800, 600), (400, 300));
+        assert_eq!(resize_config.dimensions(400, 300), (400, 300));
+    }
 }
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+/// Enum representing the compression used by the [`Codec::Tiff`](super::Codec::Tiff) codec.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::config::TiffCompression;
+///
+/// assert_eq!(TiffCompression::from_str("deflate"), Ok(TiffCompression::Deflate));
+/// assert_eq!(TiffCompression::default(), TiffCompression::Lzw);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TiffCompression {
+    /// Uncompressed image data.
+    None,
+
+    /// LZW compression, supported by virtually every TIFF reader.
+    #[default]
+    Lzw,
+
+    /// Deflate compression, usually smaller than LZW.
+    Deflate,
+}
+
+impl FromStr for TiffCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "none" | "uncompressed" => Self::None,
+            "lzw" => Self::Lzw,
+            "deflate" | "zip" => Self::Deflate,
+            compression => return Err(format!("{compression} is not valid TIFF compression")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_compression() {
+        assert_eq!(TiffCompression::from_str("none"), Ok(TiffCompression::None));
+        assert_eq!(TiffCompression::from_str("LZW"), Ok(TiffCompression::Lzw));
+        assert_eq!(
+            TiffCompression::from_str("zip"),
+            Ok(TiffCompression::Deflate)
+        );
+    }
+
+    #[test]
+    fn to_unknown() {
+        let compression = TiffCompression::from_str("jpeg");
+        assert_eq!(
+            compression.unwrap_err(),
+            "jpeg is not valid TIFF compression"
+        );
+    }
+}
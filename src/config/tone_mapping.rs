@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+/// Enum representing the operator used to map HDR images onto the SDR range.
+///
+/// Tone mapping is applied by the [`Encoder`](crate::Encoder) when the target
+/// [`Codec`](super::Codec) can't store floating point images, the exposure and peak
+/// luminance are set with [`ToneMappingConfig`](super::ToneMappingConfig). Only floating point
+/// images, such as decoded Radiance HDR, OpenEXR and PQ or HLG JPEG XL and HEIF files, are tone
+/// mapped.
+///
+/// # Examples
+///
+/// ```
+/// use std::str::FromStr;
+/// use rimage::config::ToneMapping;
+///
+/// assert_eq!(ToneMapping::from_str("hable"), Ok(ToneMapping::Hable));
+/// assert_eq!(ToneMapping::default(), ToneMapping::Aces);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    /// Clips values above the reference white.
+    Clip,
+
+    /// Extended Reinhard operator, mapping the peak luminance to white.
+    Reinhard,
+
+    /// Filmic curve fitted to the ACES reference rendering transform.
+    #[default]
+    Aces,
+
+    /// Filmic curve by John Hable, used in Uncharted 2.
+    Hable,
+
+    /// EETF from ITU-R BT.2390, compressing highlights in the PQ domain.
+    Bt2390,
+}
+
+impl FromStr for ToneMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "clip" => Self::Clip,
+            "reinhard" => Self::Reinhard,
+            "aces" => Self::Aces,
+            "hable" => Self::Hable,
+            "bt2390" | "bt.2390" => Self::Bt2390,
+            operator => return Err(format!("{operator} is not valid tone mapping operator")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_tone_mapping() {
+        assert_eq!(ToneMapping::from_str("clip"), Ok(ToneMapping::Clip));
+        assert_eq!(ToneMapping::from_str("reinhard"), Ok(ToneMapping::Reinhard));
+        assert_eq!(ToneMapping::from_str("aces"), Ok(ToneMapping::Aces));
+        assert_eq!(ToneMapping::from_str("hable"), Ok(ToneMapping::Hable));
+        assert_eq!(ToneMapping::from_str("bt2390"), Ok(ToneMapping::Bt2390));
+        assert_eq!(ToneMapping::from_str("bt.2390"), Ok(ToneMapping::Bt2390));
+    }
+
+    #[test]
+    fn to_unknown() {
+        let operator = ToneMapping::from_str("filmic");
+        assert_eq!(
+            operator.unwrap_err(),
+            "filmic is not valid tone mapping operator"
+        );
+    }
+}
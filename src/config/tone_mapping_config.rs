@@ -0,0 +1,179 @@
+use crate::error::InvalidToneMappingConfig;
+
+use super::tone_mapping::ToneMapping;
+
+/// Configuration struct for HDR to SDR tone mapping.
+///
+/// Linear values of floating point images are scaled by the exposure and compressed by the
+/// [`ToneMapping`] operator, with 1.0 treated as the SDR reference white of 203 nits and the
+/// peak luminance describing the brightest highlight of the source image.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::config::{ToneMapping, ToneMappingConfig};
+///
+/// let config = ToneMappingConfig::new(ToneMapping::Bt2390)
+///     .with_exposure(-0.5).unwrap()
+///     .with_peak_luminance(4000.0).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToneMappingConfig {
+    /// The operator compressing the HDR range.
+    operator: ToneMapping,
+
+    /// The exposure adjustment in stops, ranging from -20.0 to 20.0.
+    exposure: f32,
+
+    /// The peak luminance of the source image in nits, ranging from 203.0 to 10000.0.
+    peak_luminance: f32,
+}
+
+impl ToneMappingConfig {
+    /// Creates a new [`ToneMappingConfig`] with the specified operator.
+    ///
+    /// # Parameters
+    ///
+    /// - `operator`: The [`ToneMapping`] operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::{ToneMapping, ToneMappingConfig};
+    ///
+    /// let config = ToneMappingConfig::new(ToneMapping::Reinhard);
+    /// ```
+    pub fn new(operator: ToneMapping) -> Self {
+        Self {
+            operator,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the exposure adjustment applied before tone mapping.
+    ///
+    /// # Parameters
+    ///
+    /// - `exposure`: The exposure in stops, ranging from -20.0 to 20.0.
+    ///
+    /// # Returns
+    ///
+    /// Returns a [`Result`] containing the updated [`ToneMappingConfig`], or an
+    /// [`InvalidToneMappingConfig`] error if the exposure is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::ToneMappingConfig;
+    ///
+    /// let config = ToneMappingConfig::default().with_exposure(1.5).unwrap();
+    /// ```
+    pub fn with_exposure(mut self, exposure: f32) -> Result<Self, InvalidToneMappingConfig> {
+        if !(-20.0..=20.0).contains(&exposure) {
+            return Err(InvalidToneMappingConfig::ExposureOutOfBounds(exposure));
+        }
+
+        self.exposure = exposure;
+        Ok(self)
+    }
+
+    /// Sets the peak luminance of the source image.
+    ///
+    /// # Parameters
+    ///
+    /// - `peak_luminance`: The peak luminance in nits, ranging from 203.0 to 10000.0.
+    ///
+    /// # Returns
+    ///
+    /// Returns a [`Result`] containing the updated [`ToneMappingConfig`], or an
+    /// [`InvalidToneMappingConfig`] error if the peak luminance is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::config::ToneMappingConfig;
+    ///
+    /// let config = ToneMappingConfig::default().with_peak_luminance(4000.0).unwrap();
+    /// ```
+    pub fn with_peak_luminance(
+        mut self,
+        peak_luminance: f32,
+    ) -> Result<Self, InvalidToneMappingConfig> {
+        if !(203.0..=10000.0).contains(&peak_luminance) {
+            return Err(InvalidToneMappingConfig::PeakLuminanceOutOfBounds(
+                peak_luminance,
+            ));
+        }
+
+        self.peak_luminance = peak_luminance;
+        Ok(self)
+    }
+
+    /// Returns the tone mapping operator.
+    #[inline]
+    pub fn operator(&self) -> ToneMapping {
+        self.operator
+    }
+
+    /// Returns the exposure adjustment in stops.
+    #[inline]
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    /// Returns the peak luminance in nits.
+    #[inline]
+    pub fn peak_luminance(&self) -> f32 {
+        self.peak_luminance
+    }
+}
+
+impl Default for ToneMappingConfig {
+    fn default() -> Self {
+        Self {
+            operator: ToneMapping::default(),
+            exposure: 0.0,
+            peak_luminance: 1000.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_tone_mapping_config() {
+        let config = ToneMappingConfig::new(ToneMapping::Hable);
+
+        assert_eq!(config.operator(), ToneMapping::Hable);
+        assert_eq!(config.exposure(), 0.0);
+        assert_eq!(config.peak_luminance(), 1000.0);
+    }
+
+    #[test]
+    fn configure_exposure() {
+        let config = ToneMappingConfig::default().with_exposure(-2.0).unwrap();
+        assert_eq!(config.exposure(), -2.0);
+
+        assert!(ToneMappingConfig::default().with_exposure(21.0).is_err());
+        assert!(ToneMappingConfig::default()
+            .with_exposure(f32::NAN)
+            .is_err());
+    }
+
+    #[test]
+    fn configure_peak_luminance() {
+        let config = ToneMappingConfig::default()
+            .with_peak_luminance(4000.0)
+            .unwrap();
+        assert_eq!(config.peak_luminance(), 4000.0);
+
+        assert!(ToneMappingConfig::default()
+            .with_peak_luminance(100.0)
+            .is_err());
+        assert!(ToneMappingConfig::default()
+            .with_peak_luminance(20000.0)
+            .is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::io::Seek;
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Cursor},
     path::Path,
 };
 
@@ -11,12 +11,26 @@ use image::{DynamicImage, ImageError, ImageResult};
 
 use crate::config::ImageFormat;
 
+mod pages;
+#[cfg(feature = "raw")]
+mod raw;
+#[cfg(all(feature = "svg", feature = "resizing"))]
+use crate::config::ResizeConfig;
+
 /// Decoder for reading and decoding images from various formats.
 pub struct Decoder<R: BufRead + Seek> {
     r: ImageReader<R>,
     format: Option<ImageFormat>,
+    extension: Option<ImageFormat>,
+    page: Option<usize>,
     #[cfg(feature = "transform")]
     fix_orientation: Option<u32>,
+    #[cfg(feature = "svg")]
+    dpi: f32,
+    #[cfg(feature = "svg")]
+    resources_dir: Option<std::path::PathBuf>,
+    #[cfg(all(feature = "svg", feature = "resizing"))]
+    resize_config: Option<ResizeConfig>,
 }
 
 impl<R: BufRead + Seek> Decoder<R> {
@@ -34,8 +48,16 @@ impl<R: BufRead + Seek> Decoder<R> {
         Self {
             r: ImageReader::new(r),
             format: None,
+            extension: None,
+            page: None,
             #[cfg(feature = "transform")]
             fix_orientation: None,
+            #[cfg(feature = "svg")]
+            dpi: 96.0,
+            #[cfg(feature = "svg")]
+            resources_dir: None,
+            #[cfg(all(feature = "svg", feature = "resizing"))]
+            resize_config: None,
         }
     }
 
@@ -54,6 +76,85 @@ impl<R: BufRead + Seek> Decoder<R> {
         self
     }
 
+    /// Gets the image format of the decoder, if known.
+    ///
+    /// The format is detected from the image content by [`Decoder::from_path`] and
+    /// [`Decoder::from_bytes`], or set explicitly with [`Decoder::with_format`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::ImageFormat, Decoder};
+    ///
+    /// let decoder = Decoder::from_bytes(b"\x89PNG\r\n\x1a\n");
+    ///
+    /// assert_eq!(decoder.format(), Some(ImageFormat::Png));
+    /// ```
+    #[inline]
+    pub fn format(&self) -> Option<ImageFormat> {
+        self.format
+    }
+
+    /// Checks if the file extension disagrees with the detected image content.
+    ///
+    /// # Returns
+    ///
+    /// Returns the format suggested by the file extension and the format detected from the
+    /// content, or `None` if they match or one of them is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs;
+    /// use rimage::{config::ImageFormat, Decoder};
+    ///
+    /// # fs::copy("tests/files/png/f1t.png", "png_named.jpg")?;
+    /// let decoder = Decoder::from_path("png_named.jpg")?;
+    ///
+    /// assert_eq!(decoder.format_mismatch(), Some((ImageFormat::Jpeg, ImageFormat::Png)));
+    /// # fs::remove_file("png_named.jpg")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn format_mismatch(&self) -> Option<(ImageFormat, ImageFormat)> {
+        match (self.extension, self.format) {
+            (Some(extension), Some(content)) if extension != content => Some((extension, content)),
+            _ => None,
+        }
+    }
+
+    /// Selects the page of a multi-page image to decode.
+    ///
+    /// Pages are counted from 0 in the order they are stored: TIFF pages, or ICO sub-images in
+    /// the icon directory order. Without a selected page ICO images decode to their largest
+    /// sub-image. Other formats only have page 0, decoding any other page fails.
+    ///
+    /// # Parameters
+    ///
+    /// - `page`: The index of the page.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified [`Decoder`] with the specified page.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs;
+    /// use rimage::Decoder;
+    ///
+    /// let bytes = fs::read("tests/files/png/f1t.png")?;
+    ///
+    /// assert!(Decoder::from_bytes(&bytes).with_page(0).decode().is_ok());
+    /// assert!(Decoder::from_bytes(&bytes).with_page(1).decode().is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+        self
+    }
+
     /// Sets the fixed orientation for image decoding.
     ///
     /// This method allows you to specify a fixed orientation for decoding images that may have
@@ -87,25 +188,171 @@ impl<R: BufRead + Seek> Decoder<R> {
         self
     }
 
+    /// Sets the resolution used to rasterize vector images.
+    ///
+    /// SVG sizes are defined in CSS pixels at 96 DPI, a higher resolution renders a
+    /// proportionally larger image. Ignored for raster images and when a target size is set
+    /// with [`Decoder::with_resize_config`].
+    ///
+    /// # Parameters
+    ///
+    /// - `dpi`: The rendering resolution in dots per inch, 96 by default.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified [`Decoder`] with the specified resolution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::Decoder;
+    ///
+    /// let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="8"/>"#;
+    ///
+    /// let image = Decoder::from_bytes(svg).with_dpi(192.0).decode()?;
+    ///
+    /// assert_eq!((image.width(), image.height()), (32, 16));
+    /// # Ok::<(), image::ImageError>(())
+    /// ```
+    #[inline]
+    #[cfg(feature = "svg")]
+    pub fn with_dpi(mut self, dpi: f32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Sets the target size for rasterizing vector images.
+    ///
+    /// Vector images are rendered directly at the size the [`ResizeConfig`] would resize them
+    /// to, so encoding with the same configuration keeps them crisp instead of resampling a
+    /// default sized rendering. Raster images are not affected.
+    ///
+    /// # Parameters
+    ///
+    /// - `config`: The resize configuration used by the encoder.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified [`Decoder`] with the specified target size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{config::ResizeConfig, Decoder};
+    ///
+    /// let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="8"/>"#;
+    ///
+    /// let image = Decoder::from_bytes(svg)
+    ///     .with_resize_config(ResizeConfig::default().with_width(512))
+    ///     .decode()?;
+    ///
+    /// assert_eq!((image.width(), image.height()), (512, 256));
+    /// # Ok::<(), image::ImageError>(())
+    /// ```
+    #[inline]
+    #[cfg(all(feature = "svg", feature = "resizing"))]
+    pub fn with_resize_config(mut self, config: ResizeConfig) -> Self {
+        self.resize_config = Some(config);
+        self
+    }
+
     /// Decodes the image using the specified format and input data.
     ///
     /// # Returns
     ///
     /// Returns a [`Result`] containing the decoded [`Image`] on success or a [`DecoderError`] on failure.
     pub fn decode(self) -> ImageResult<DynamicImage> {
+        let decoder = self.detect_format()?;
+
         #[cfg(feature = "transform")]
-        let orientation = self.fix_orientation;
+        let orientation = decoder.orientation();
 
-        #[allow(unused_mut)]
-        let mut image = match self.format {
-            #[cfg(feature = "jxl")]
-            Some(ImageFormat::JpegXl) => self.decode_jpegxl(),
-            #[cfg(feature = "avif")]
-            Some(ImageFormat::Avif) => self.decode_avif(),
-            _ => self.r.with_guessed_format()?.decode(),
-        }?;
+        let image = match (decoder.format, decoder.page) {
+            (Some(ImageFormat::Tiff), Some(page)) => {
+                pages::decode_tiff_page(decoder.r.into_inner(), page)?
+            }
+            (Some(ImageFormat::Ico), Some(page)) => {
+                pages::decode_ico_entry(decoder.r.into_inner(), page)?
+            }
+            (_, Some(page)) if page != 0 => return Err(pages::missing_page(page)),
+            _ => decoder.decode_image()?,
+        };
 
         #[cfg(feature = "transform")]
+        let image = Self::orient(image, orientation);
+
+        Ok(image)
+    }
+
+    /// Decodes every page of a multi-page image.
+    ///
+    /// TIFF images decode to all of their pages and ICO images to all of their sub-images,
+    /// other formats decode to a single image.
+    ///
+    /// # Returns
+    ///
+    /// Returns a [`Result`] containing the decoded pages in the order they are stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs;
+    /// use rimage::Decoder;
+    ///
+    /// let bytes = fs::read("tests/files/png/f1t.png")?;
+    ///
+    /// let pages = Decoder::from_bytes(&bytes).decode_pages()?;
+    ///
+    /// assert_eq!(pages.len(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_pages(self) -> ImageResult<Vec<DynamicImage>> {
+        let decoder = self.detect_format()?;
+
+        #[cfg(feature = "transform")]
+        let orientation = decoder.orientation();
+
+        let images = match decoder.format {
+            Some(ImageFormat::Tiff) => pages::decode_tiff_pages(decoder.r.into_inner())?,
+            Some(ImageFormat::Ico) => pages::decode_ico_entries(decoder.r.into_inner())?,
+            _ => vec![decoder.decode_image()?],
+        };
+
+        #[cfg(feature = "transform")]
+        let images = images
+            .into_iter()
+            .map(|image| Self::orient(image, orientation))
+            .collect();
+
+        Ok(images)
+    }
+
+    /// Detects the image format from content when it's not set.
+    fn detect_format(mut self) -> ImageResult<Self> {
+        if self.format.is_none() {
+            let mut r = self.r.into_inner();
+            self.format = ImageFormat::from_bytes(r.fill_buf()?).ok();
+            self.r = ImageReader::new(r);
+        }
+
+        Ok(self)
+    }
+
+    /// Gets the orientation applied after decoding.
+    #[cfg(feature = "transform")]
+    fn orientation(&self) -> Option<u32> {
+        // these decoders apply orientation from the image metadata themselves
+        match self.format {
+            #[cfg(feature = "heif")]
+            Some(ImageFormat::Heif) => None,
+            #[cfg(feature = "raw")]
+            Some(ImageFormat::Raw) => None,
+            _ => self.fix_orientation,
+        }
+    }
+
+    #[cfg(feature = "transform")]
+    fn orient(mut image: DynamicImage, orientation: Option<u32>) -> DynamicImage {
         if let Some(orientation) = orientation {
             if orientation <= 8 {
                 let orientation = orientation - 1;
@@ -125,16 +372,62 @@ impl<R: BufRead + Seek> Decoder<R> {
             }
         }
 
-        Ok(image)
+        image
+    }
+
+    /// Decodes the image with the decoder for its format.
+    fn decode_image(mut self) -> ImageResult<DynamicImage> {
+        match self.format {
+            #[cfg(feature = "jxl")]
+            Some(ImageFormat::JpegXl) => self.decode_jpegxl(),
+            #[cfg(feature = "avif")]
+            Some(ImageFormat::Avif) => self.decode_avif(),
+            #[cfg(feature = "heif")]
+            Some(ImageFormat::Heif) => self.decode_heif(),
+            #[cfg(feature = "svg")]
+            Some(ImageFormat::Svg) => self.decode_svg(),
+            #[cfg(feature = "raw")]
+            Some(ImageFormat::Raw) => raw::decode(&mut self.r.into_inner()),
+            Some(ImageFormat::Hdr) => self.decode_hdr(),
+            // TGA has no signature to guess the format from
+            Some(ImageFormat::Tga) => {
+                self.r.set_format(image::ImageFormat::Tga);
+                self.r.decode()
+            }
+            _ => self.r.with_guessed_format()?.decode(),
+        }
+    }
+
+    /// Decodes Radiance HDR images to floating point, `image` only provides them clamped to 8-bit.
+    fn decode_hdr(self) -> ImageResult<DynamicImage> {
+        use image::codecs::hdr::HdrDecoder;
+        use image::Rgb32FImage;
+
+        let decoder = HdrDecoder::new(self.r.into_inner())?;
+        let meta = decoder.metadata();
+
+        let pixels = decoder
+            .read_image_hdr()?
+            .into_iter()
+            .flat_map(|pixel| pixel.0)
+            .collect();
+
+        Ok(DynamicImage::ImageRgb32F(
+            Rgb32FImage::from_raw(meta.width, meta.height, pixels).unwrap(),
+        ))
     }
 
     #[cfg(feature = "jxl")]
     fn decode_jpegxl(self) -> ImageResult<DynamicImage> {
         use image::error::{DecodingError, UnsupportedErrorKind};
-        use image::DynamicImage::{ImageLuma8, ImageLumaA8, ImageRgb8, ImageRgba8};
-        use image::{GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+        use image::DynamicImage::{
+            ImageLuma8, ImageLumaA8, ImageRgb32F, ImageRgb8, ImageRgba32F, ImageRgba8,
+        };
+        use image::{GrayAlphaImage, GrayImage, Rgb32FImage, RgbImage, Rgba32FImage, RgbaImage};
 
-        use jxl_oxide::{JxlImage, PixelFormat};
+        use jxl_oxide::{color::TransferFunction, JxlImage, PixelFormat};
+
+        use crate::operations::tone_mapping::{linearize, Transfer};
 
         let image = JxlImage::from_reader(self.r.into_inner()).map_err(|e| {
             ImageError::Decoding(DecodingError::new(
@@ -153,6 +446,50 @@ impl<R: BufRead + Seek> Decoder<R> {
 
         let framebuffer = render.image();
 
+        // PQ and HLG signals are decoded to linear light, to be tone mapped like other HDR images
+        let transfer = match image.image_header().metadata.colour_encoding.tf {
+            TransferFunction::Pq => Some(Transfer::Pq),
+            TransferFunction::Hlg => Some(Transfer::Hlg),
+            _ => None,
+        };
+
+        if let Some(transfer) = transfer {
+            let (width, height) = (framebuffer.width() as u32, framebuffer.height() as u32);
+            let buf = framebuffer.buf();
+
+            let signals = match format {
+                PixelFormat::Gray => Some(ImageRgb32F(
+                    Rgb32FImage::from_raw(
+                        width,
+                        height,
+                        buf.iter().flat_map(|&g| [g; 3]).collect(),
+                    )
+                    .unwrap(),
+                )),
+                PixelFormat::Graya => Some(ImageRgba32F(
+                    Rgba32FImage::from_raw(
+                        width,
+                        height,
+                        buf.chunks_exact(2)
+                            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                            .collect(),
+                    )
+                    .unwrap(),
+                )),
+                PixelFormat::Rgb => Some(ImageRgb32F(
+                    Rgb32FImage::from_raw(width, height, buf.to_vec()).unwrap(),
+                )),
+                PixelFormat::Rgba => Some(ImageRgba32F(
+                    Rgba32FImage::from_raw(width, height, buf.to_vec()).unwrap(),
+                )),
+                _ => None,
+            };
+
+            if let Some(signals) = signals {
+                return Ok(linearize(signals, transfer));
+            }
+        }
+
         Ok(match format {
             PixelFormat::Gray => ImageLuma8(
                 GrayImage::from_raw(
@@ -232,13 +569,249 @@ impl<R: BufRead + Seek> Decoder<R> {
             ))
         })
     }
+
+    #[cfg(feature = "heif")]
+    fn decode_heif(self) -> ImageResult<DynamicImage> {
+        use image::error::DecodingError;
+        use image::{ImageBuffer, Rgb, Rgba};
+        use libheif_rs::{
+            ColorSpace, HeifContext, HeifError, LibHeif, RgbChroma, TransferCharacteristics,
+        };
+
+        use crate::operations::tone_mapping::{linearize, Transfer};
+
+        let mut buf: Vec<u8> = vec![];
+
+        self.r.into_inner().read_to_end(&mut buf)?;
+
+        let decoding_error = |e: HeifError| {
+            ImageError::Decoding(DecodingError::new(
+                ImageFormatHint::Name("HEIF".to_string()),
+                e,
+            ))
+        };
+
+        let context = HeifContext::read_from_bytes(&buf).map_err(decoding_error)?;
+        let handle = context.primary_image_handle().map_err(decoding_error)?;
+
+        let alpha = handle.has_alpha_channel();
+        let bits = handle.luma_bits_per_pixel();
+
+        let chroma = match (bits > 8, alpha) {
+            (false, false) => RgbChroma::Rgb,
+            (false, true) => RgbChroma::Rgba,
+            (true, false) => RgbChroma::HdrRgbLe,
+            (true, true) => RgbChroma::HdrRgbaLe,
+        };
+
+        // libheif assembles grid tiles, attaches the alpha auxiliary image and applies
+        // rotation and mirroring transformations
+        let image = LibHeif::new()
+            .decode(&handle, ColorSpace::Rgb(chroma), None)
+            .map_err(decoding_error)?;
+
+        let premultiplied = image.is_premultiplied_alpha();
+        let planes = image.planes();
+
+        let plane = planes.interleaved.ok_or_else(|| {
+            ImageError::Decoding(DecodingError::new(
+                ImageFormatHint::Name("HEIF".to_string()),
+                "missing interleaved plane",
+            ))
+        })?;
+
+        let (width, height) = (plane.width, plane.height);
+        let channels = if alpha { 4 } else { 3 };
+        let row = width as usize * channels * if bits > 8 { 2 } else { 1 };
+
+        let data = plane
+            .data
+            .chunks(plane.stride)
+            .take(height as usize)
+            .flat_map(|line| &line[..row]);
+
+        let mut image = if bits > 8 {
+            let max = ((1u32 << bits) - 1) as f32;
+
+            let data: Vec<u16> = data
+                .copied()
+                .collect::<Vec<u8>>()
+                .chunks_exact(2)
+                .map(|v| u16::from_le_bytes([v[0], v[1]]))
+                .map(|v| (v as f32 / max * u16::MAX as f32).round() as u16)
+                .collect();
+
+            if alpha {
+                DynamicImage::ImageRgba16(
+                    ImageBuffer::<Rgba<u16>, _>::from_raw(width, height, data).unwrap(),
+                )
+            } else {
+                DynamicImage::ImageRgb16(
+                    ImageBuffer::<Rgb<u16>, _>::from_raw(width, height, data).unwrap(),
+                )
+            }
+        } else {
+            let data: Vec<u8> = data.copied().collect();
+
+            if alpha {
+                DynamicImage::ImageRgba8(
+                    ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, data).unwrap(),
+                )
+            } else {
+                DynamicImage::ImageRgb8(
+                    ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, data).unwrap(),
+                )
+            }
+        };
+
+        if premultiplied {
+            match &mut image {
+                DynamicImage::ImageRgba8(image) => image.pixels_mut().for_each(|p| {
+                    let a = p[3] as u32;
+
+                    for c in &mut p.0[..3] {
+                        if let Some(value) = (*c as u32 * 255).checked_div(a) {
+                            *c = value.min(255) as u8;
+                        }
+                    }
+                }),
+                DynamicImage::ImageRgba16(image) => image.pixels_mut().for_each(|p| {
+                    let a = p[3] as u64;
+
+                    for c in &mut p.0[..3] {
+                        if let Some(value) = (*c as u64 * 65535).checked_div(a) {
+                            *c = value.min(65535) as u16;
+                        }
+                    }
+                }),
+                _ => {}
+            }
+        }
+
+        // PQ and HLG signals are decoded to linear light, to be tone mapped like other HDR images
+        let transfer = handle.color_profile_nclx().and_then(|profile| {
+            match profile.transfer_characteristics() {
+                TransferCharacteristics::ITU_R_BT_2100_0_PQ => Some(Transfer::Pq),
+                TransferCharacteristics::ITU_R_BT_2100_0_HLG => Some(Transfer::Hlg),
+                _ => None,
+            }
+        });
+
+        Ok(match transfer {
+            Some(transfer) => linearize(image, transfer),
+            None => image,
+        })
+    }
+
+    #[cfg(feature = "svg")]
+    fn decode_svg(self) -> ImageResult<DynamicImage> {
+        use image::error::{
+            DecodingError, LimitError, LimitErrorKind, ParameterError, ParameterErrorKind,
+        };
+        use image::RgbaImage;
+        use resvg::{tiny_skia, usvg};
+
+        let scale = self.dpi / 96.0;
+
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(ImageError::Parameter(ParameterError::from_kind(
+                ParameterErrorKind::Generic(format!("{} is not valid DPI", self.dpi)),
+            )));
+        }
+
+        let mut data = vec![];
+        self.r.into_inner().read_to_end(&mut data)?;
+
+        let options = usvg::Options {
+            resources_dir: self.resources_dir,
+            fontdb: system_fonts(),
+            ..Default::default()
+        };
+
+        let tree = usvg::Tree::from_data(&data, &options).map_err(|e| {
+            ImageError::Decoding(DecodingError::new(
+                ImageFormatHint::Name("SVG".to_string()),
+                e,
+            ))
+        })?;
+
+        let size = tree.size();
+
+        let (width, height) = (
+            ((size.width() * scale).round() as u32).max(1),
+            ((size.height() * scale).round() as u32).max(1),
+        );
+
+        #[cfg(feature = "resizing")]
+        let (width, height) = match self.resize_config {
+            Some(config) => config.dimensions(width, height),
+            None => (width, height),
+        };
+
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or(ImageError::Limits(
+            LimitError::from_kind(LimitErrorKind::DimensionError),
+        ))?;
+
+        resvg::render(
+            &tree,
+            tiny_skia::Transform::from_scale(
+                width as f32 / size.width(),
+                height as f32 / size.height(),
+            ),
+            &mut pixmap.as_mut(),
+        );
+
+        // tiny-skia renders with premultiplied alpha
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        Ok(DynamicImage::ImageRgba8(
+            RgbaImage::from_raw(width, height, pixels).unwrap(),
+        ))
+    }
+}
+
+impl<'a> Decoder<Cursor<&'a [u8]>> {
+    /// Creates a new [`Decoder`] from an in-memory image.
+    ///
+    /// The image format is detected from the content, see [`ImageFormat::from_bytes`].
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The encoded image data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs;
+    /// use rimage::Decoder;
+    ///
+    /// let bytes = fs::read("tests/files/png/f1t.png")?;
+    ///
+    /// let image = Decoder::from_bytes(&bytes).decode()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8]) -> Self {
+        let mut decoder = Self::new(Cursor::new(bytes));
+        decoder.format = ImageFormat::from_bytes(bytes).ok();
+        decoder
+    }
 }
 
 impl Decoder<BufReader<File>> {
     /// Creates a new [`Decoder`] from a file specified by the given path.
     ///
     /// This method opens the file at the specified path, sets up a `Reader` for reading, and
-    /// determines the image format based on the file extension.
+    /// determines the image format from the file content, falling back to the file extension
+    /// for formats without a recognizable signature. Use [`Decoder::format_mismatch`] to check
+    /// if the extension disagrees with the content.
     ///
     /// # Parameters
     ///
@@ -255,13 +828,48 @@ impl Decoder<BufReader<File>> {
     /// the image format, or other I/O-related errors.
     #[inline]
     pub fn from_path(path: impl AsRef<Path>) -> ImageResult<Self> {
+        let path = path.as_ref();
+
+        let mut r = BufReader::new(File::open(path)?);
+
+        let extension = ImageFormat::from_path(path).ok();
+        let content = ImageFormat::from_bytes(r.fill_buf()?).ok();
+
+        // most camera raw formats are TIFF containers, only the extension tells them apart
+        #[cfg(feature = "raw")]
+        let content = match (content, extension) {
+            (Some(ImageFormat::Tiff), Some(ImageFormat::Raw)) => extension,
+            _ => content,
+        };
+
+        let format = content.or(extension);
+
+        if format.is_none() {
+            return Err(ImageError::Unsupported(UnsupportedError::from(
+                ImageFormatHint::Unknown,
+            )));
+        }
+
+        let mut reader = ImageReader::new(r);
+
+        if let Ok(format) = image::ImageFormat::from_path(path) {
+            reader.set_format(format);
+        }
+
         Ok(Self {
-            r: ImageReader::open(path.as_ref())?,
-            format: Some(ImageFormat::from_path(path.as_ref()).map_err(|_| {
-                ImageError::Unsupported(UnsupportedError::from(ImageFormatHint::Unknown))
-            })?),
+            r: reader,
+            format,
+            extension,
+            page: None,
             #[cfg(feature = "transform")]
-            fix_orientation: Self::get_orientation(path.as_ref()),
+            fix_orientation: Self::get_orientation(path),
+            #[cfg(feature = "svg")]
+            dpi: 96.0,
+            // relative references in SVG documents are resolved against the file location
+            #[cfg(feature = "svg")]
+            resources_dir: path.parent().map(Path::to_path_buf),
+            #[cfg(all(feature = "svg", feature = "resizing"))]
+            resize_config: None,
         })
     }
 
@@ -285,5 +893,23 @@ impl Decoder<BufReader<File>> {
     }
 }
 
+/// Gets the font database used to render text in SVG documents, loaded once.
+#[cfg(feature = "svg")]
+fn system_fonts() -> std::sync::Arc<resvg::usvg::fontdb::Database> {
+    use std::sync::{Arc, OnceLock};
+
+    use resvg::usvg::fontdb::Database;
+
+    static FONTS: OnceLock<Arc<Database>> = OnceLock::new();
+
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,322 @@
+//! Decoding of single pages and sub-images from multi-page TIFF and ICO containers.
+
+use std::io::{Cursor, Read, Seek};
+
+use image::codecs::ico::IcoDecoder;
+use image::error::{DecodingError, ParameterError, ParameterErrorKind, UnsupportedError};
+use image::error::{ImageFormatHint, UnsupportedErrorKind};
+use image::{DynamicImage, ImageBuffer, ImageError, ImageResult};
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
+use tiff::ColorType;
+
+/// Size of the ICO header.
+const ICO_HEADER_SIZE: usize = 6;
+
+/// Size of a single ICO directory entry.
+const ICO_ENTRY_SIZE: usize = 16;
+
+/// Error returned when the requested page doesn't exist.
+pub(crate) fn missing_page(page: usize) -> ImageError {
+    ImageError::Parameter(ParameterError::from_kind(ParameterErrorKind::Generic(
+        format!("Page {page} doesn't exist"),
+    )))
+}
+
+/// Decodes the TIFF page at the specified index.
+pub(crate) fn decode_tiff_page<R: Read + Seek>(r: R, page: usize) -> ImageResult<DynamicImage> {
+    let mut decoder = TiffDecoder::new(r).map_err(tiff_error)?;
+
+    for _ in 0..page {
+        if !decoder.more_images() {
+            return Err(missing_page(page));
+        }
+
+        decoder.next_image().map_err(tiff_error)?;
+    }
+
+    read_tiff_page(&mut decoder)
+}
+
+/// Decodes every page of the TIFF image.
+pub(crate) fn decode_tiff_pages<R: Read + Seek>(r: R) -> ImageResult<Vec<DynamicImage>> {
+    let mut decoder = TiffDecoder::new(r).map_err(tiff_error)?;
+    let mut pages = vec![read_tiff_page(&mut decoder)?];
+
+    while decoder.more_images() {
+        decoder.next_image().map_err(tiff_error)?;
+        pages.push(read_tiff_page(&mut decoder)?);
+    }
+
+    Ok(pages)
+}
+
+/// Reads the current page of the TIFF decoder.
+///
+/// Pages support the same color types as the first page decoded by `image`, palette and YCbCr
+/// pages are reported as unsupported.
+fn read_tiff_page<R: Read + Seek>(decoder: &mut TiffDecoder<R>) -> ImageResult<DynamicImage> {
+    let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+    let color = match decoder.colortype() {
+        Ok(color) => color,
+        Err(tiff::TiffError::UnsupportedError(e)) => return Err(tiff_unsupported(e.to_string())),
+        Err(e) => return Err(tiff_error(e)),
+    };
+    let data = decoder.read_image().map_err(tiff_error)?;
+
+    let image = match (color, data) {
+        (ColorType::Gray(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma8)
+        }
+        (ColorType::Gray(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma16)
+        }
+        (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA8)
+        }
+        (ColorType::GrayA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA16)
+        }
+        (ColorType::RGB(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+        }
+        (ColorType::RGB(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb16)
+        }
+        (ColorType::RGB(32), DecodingResult::F32(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb32F)
+        }
+        (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+        }
+        (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba16)
+        }
+        (ColorType::RGBA(32), DecodingResult::F32(data)) => {
+            ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba32F)
+        }
+        (ColorType::CMYK(8), DecodingResult::U8(data)) => {
+            let rgb = data
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let k = 255 - cmyk[3] as u16;
+
+                    [0, 1, 2].map(|c| ((255 - cmyk[c] as u16) * k / 255) as u8)
+                })
+                .collect();
+
+            ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb8)
+        }
+        (color, _) => return Err(tiff_unsupported(format!("{color:?} color type"))),
+    };
+
+    image.ok_or_else(|| {
+        tiff_error(tiff::TiffError::FormatError(
+            tiff::TiffFormatError::InconsistentSizesEncountered,
+        ))
+    })
+}
+
+fn tiff_unsupported(feature: String) -> ImageError {
+    ImageError::Unsupported(UnsupportedError::from_format_and_kind(
+        ImageFormatHint::Exact(image::ImageFormat::Tiff),
+        UnsupportedErrorKind::GenericFeature(feature),
+    ))
+}
+
+fn tiff_error(e: tiff::TiffError) -> ImageError {
+    ImageError::Decoding(DecodingError::new(
+        ImageFormatHint::Exact(image::ImageFormat::Tiff),
+        e,
+    ))
+}
+
+/// Decodes the ICO sub-image at the specified index of the icon directory.
+pub(crate) fn decode_ico_entry<R: Read>(mut r: R, index: usize) -> ImageResult<DynamicImage> {
+    let mut buf = vec![];
+    r.read_to_end(&mut buf)?;
+
+    if index >= ico_entries(&buf)? {
+        return Err(missing_page(index));
+    }
+
+    read_ico_entry(&buf, index)
+}
+
+/// Decodes every sub-image of the ICO image in the icon directory order.
+pub(crate) fn decode_ico_entries<R: Read>(mut r: R) -> ImageResult<Vec<DynamicImage>> {
+    let mut buf = vec![];
+    r.read_to_end(&mut buf)?;
+
+    (0..ico_entries(&buf)?)
+        .map(|index| read_ico_entry(&buf, index))
+        .collect()
+}
+
+/// Reads the number of sub-images from the ICO header.
+fn ico_entries(buf: &[u8]) -> ImageResult<usize> {
+    if buf.len() < ICO_HEADER_SIZE {
+        return Err(ico_error("File is too short"));
+    }
+
+    let count = u16::from_le_bytes([buf[4], buf[5]]) as usize;
+
+    if buf.len() < ICO_HEADER_SIZE + count * ICO_ENTRY_SIZE {
+        return Err(ico_error("Icon directory is truncated"));
+    }
+
+    Ok(count)
+}
+
+/// Decodes a single sub-image by wrapping it into an icon with just that entry, as `image`
+/// only decodes the largest one.
+fn read_ico_entry(buf: &[u8], index: usize) -> ImageResult<DynamicImage> {
+    let entry = &buf[ICO_HEADER_SIZE + index * ICO_ENTRY_SIZE..][..ICO_ENTRY_SIZE];
+
+    let size = u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]) as usize;
+    let offset = u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]) as usize;
+
+    let data = offset
+        .checked_add(size)
+        .and_then(|end| buf.get(offset..end))
+        .ok_or_else(|| ico_error("Image data is out of bounds"))?;
+
+    let mut icon = Vec::with_capacity(ICO_HEADER_SIZE + ICO_ENTRY_SIZE + data.len());
+    icon.extend_from_slice(&[0, 0, 1, 0, 1, 0]);
+    icon.extend_from_slice(&entry[..12]);
+    icon.extend_from_slice(&((ICO_HEADER_SIZE + ICO_ENTRY_SIZE) as u32).to_le_bytes());
+    icon.extend_from_slice(data);
+
+    DynamicImage::from_decoder(IcoDecoder::new(Cursor::new(icon))?)
+}
+
+fn ico_error(message: &str) -> ImageError {
+    ImageError::Decoding(DecodingError::new(
+        ImageFormatHint::Exact(image::ImageFormat::Ico),
+        message,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use image::codecs::ico::{IcoEncoder, IcoFrame};
+    use image::ColorType as ImageColorType;
+    use tiff::encoder::{colortype, TiffEncoder};
+    use tiff::tags::{PhotometricInterpretation, Tag};
+
+    use super::*;
+
+    fn tiff(pages: &[(u32, u8)]) -> Vec<u8> {
+        let mut buf = Cursor::new(vec![]);
+        let mut encoder = TiffEncoder::new(&mut buf).unwrap();
+
+        for (size, value) in pages {
+            let data = vec![*value; (size * size * 3) as usize];
+            encoder
+                .write_image::<colortype::RGB8>(*size, *size, &data)
+                .unwrap();
+        }
+
+        buf.into_inner()
+    }
+
+    fn ico(sizes: &[u32]) -> Vec<u8> {
+        let frames: Vec<_> = sizes
+            .iter()
+            .map(|size| {
+                let data = vec![*size as u8; (size * size * 4) as usize];
+                IcoFrame::as_png(&data, *size, *size, ImageColorType::Rgba8).unwrap()
+            })
+            .collect();
+
+        let mut buf = vec![];
+        IcoEncoder::new(&mut buf).encode_images(&frames).unwrap();
+
+        buf
+    }
+
+    #[test]
+    fn tiff_pages() {
+        let buf = tiff(&[(4, 10), (8, 20), (2, 30)]);
+
+        let pages = decode_tiff_pages(Cursor::new(&buf)).unwrap();
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[1].width(), 8);
+        assert_eq!(pages[2].as_rgb8().unwrap().get_pixel(0, 0).0, [30; 3]);
+
+        let page = decode_tiff_page(Cursor::new(&buf), 1).unwrap();
+        assert_eq!(page.as_rgb8().unwrap().get_pixel(0, 0).0, [20; 3]);
+    }
+
+    #[test]
+    fn tiff_non_rgb_pages() {
+        let mut buf = Cursor::new(vec![]);
+        let mut encoder = TiffEncoder::new(&mut buf).unwrap();
+
+        encoder
+            .write_image::<colortype::RGB8>(2, 2, &[10; 2 * 2 * 3])
+            .unwrap();
+        encoder
+            .write_image::<colortype::Gray16>(2, 2, &[1000; 2 * 2])
+            .unwrap();
+
+        // indices into a color map of 256 red, green and blue entries
+        let mut image = encoder.new_image::<colortype::Gray8>(2, 2).unwrap();
+        image
+            .encoder()
+            .write_tag(
+                Tag::PhotometricInterpretation,
+                PhotometricInterpretation::RGBPalette.to_u16(),
+            )
+            .unwrap();
+        image
+            .encoder()
+            .write_tag(Tag::ColorMap, &[0u16; 3 * 256][..])
+            .unwrap();
+        image.write_data(&[0; 2 * 2]).unwrap();
+
+        let buf = buf.into_inner();
+
+        let page = decode_tiff_page(Cursor::new(&buf), 1).unwrap();
+        assert_eq!(page.as_luma16().unwrap().get_pixel(0, 0).0, [1000]);
+
+        assert!(matches!(
+            decode_tiff_page(Cursor::new(&buf), 2),
+            Err(ImageError::Unsupported(_))
+        ));
+        assert!(matches!(
+            decode_tiff_pages(Cursor::new(&buf)),
+            Err(ImageError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn tiff_missing_page() {
+        let buf = tiff(&[(4, 10)]);
+
+        assert!(matches!(
+            decode_tiff_page(Cursor::new(&buf), 1),
+            Err(ImageError::Parameter(_))
+        ));
+    }
+
+    #[test]
+    fn ico_sub_images() {
+        let buf = ico(&[16, 32, 48]);
+
+        let entries = decode_ico_entries(Cursor::new(&buf)).unwrap();
+
+        assert_eq!(
+            entries.iter().map(|e| e.width()).collect::<Vec<_>>(),
+            [16, 32, 48]
+        );
+
+        let entry = decode_ico_entry(Cursor::new(&buf), 0).unwrap();
+        assert_eq!(entry.as_rgba8().unwrap().get_pixel(0, 0).0, [16; 4]);
+
+        assert!(matches!(
+            decode_ico_entry(Cursor::new(&buf), 3),
+            Err(ImageError::Parameter(_))
+        ));
+    }
+}
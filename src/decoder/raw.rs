@@ -0,0 +1,349 @@
+//! Development of camera raw images into RGB images.
+
+use std::io::Read;
+
+use image::error::{DecodingError, ImageFormatHint};
+use image::{DynamicImage, ImageBuffer, ImageError, ImageResult, Luma, Rgb};
+use rawloader::{Orientation, RawImage, RawImageData, CFA};
+
+/// Linear sRGB (D65) to XYZ matrix.
+const SRGB_TO_XYZ: [[f32; 3]; 3] = [
+    [0.412_453, 0.357_580, 0.180_423],
+    [0.212_671, 0.715_160, 0.072_169],
+    [0.019_334, 0.119_193, 0.950_227],
+];
+
+/// Strength of the contrast curve applied after gamma encoding.
+const CONTRAST: f32 = 0.2;
+
+/// Decodes a camera raw image and develops it into a 16-bit image.
+pub(crate) fn decode(r: &mut dyn Read) -> ImageResult<DynamicImage> {
+    let raw = rawloader::decode(r).map_err(|e| {
+        ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Name("RAW".to_string()),
+            e.to_string(),
+        ))
+    })?;
+
+    Ok(develop(&raw))
+}
+
+/// Develops raw sensor data with demosaicing, white balance, color conversion to sRGB,
+/// a basic tone curve and orientation from the raw metadata.
+pub(crate) fn develop(raw: &RawImage) -> DynamicImage {
+    let [top, right, bottom, left] = raw.crops;
+    let width = raw.width.saturating_sub(left + right);
+    let height = raw.height.saturating_sub(top + bottom);
+
+    let data = normalize(raw);
+
+    let image = if raw.is_monochrome() {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y + top) * raw.width + x + left))
+            .map(|i| encode(tone(data[i])))
+            .collect();
+
+        DynamicImage::ImageLuma16(
+            ImageBuffer::<Luma<u16>, _>::from_raw(width as u32, height as u32, pixels).unwrap(),
+        )
+    } else {
+        let rgb = if raw.cpp == 3 {
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| ((y + top) * raw.width + x + left) * 3))
+                .map(|i| [data[i], data[i + 1], data[i + 2]])
+                .collect()
+        } else {
+            let cropped: Vec<f32> = (0..height)
+                .flat_map(|y| (0..width).map(move |x| (y + top) * raw.width + x + left))
+                .map(|i| data[i])
+                .collect();
+
+            demosaic(&cropped, width, height, &raw.cropped_cfa())
+        };
+
+        let wb = white_balance(raw);
+        let matrix = camera_to_srgb(raw);
+
+        let pixels = rgb
+            .into_iter()
+            .flat_map(|pixel| {
+                let balanced = [pixel[0] * wb[0], pixel[1] * wb[1], pixel[2] * wb[2]];
+
+                matrix.map(|row| {
+                    let linear: f32 = row.iter().zip(balanced).map(|(m, c)| m * c).sum();
+                    encode(tone(linear))
+                })
+            })
+            .collect();
+
+        DynamicImage::ImageRgb16(
+            ImageBuffer::<Rgb<u16>, _>::from_raw(width as u32, height as u32, pixels).unwrap(),
+        )
+    };
+
+    orient(image, &raw.orientation)
+}
+
+/// Scales sensor values between black and white levels to 0.0..1.0 range.
+fn normalize(raw: &RawImage) -> Vec<f32> {
+    let channel = |i: usize| {
+        if raw.cpp == 1 {
+            let (row, col) = (i / raw.width, i % raw.width);
+            raw.cfa.color_at(row, col).min(3)
+        } else {
+            i % raw.cpp
+        }
+    };
+
+    match &raw.data {
+        RawImageData::Integer(data) => data
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let c = channel(i);
+                let (black, white) = (raw.blacklevels[c] as f32, raw.whitelevels[c] as f32);
+
+                if white > black {
+                    (*v as f32 - black) / (white - black)
+                } else {
+                    *v as f32 / u16::MAX as f32
+                }
+            })
+            .collect(),
+        // floating point DNGs store already normalized values
+        RawImageData::Float(data) => data.clone(),
+    }
+}
+
+/// Maps a CFA color index to RGB channel, the fourth color (emerald or second green) is
+/// treated as green.
+#[inline]
+fn rgb_channel(color: usize) -> usize {
+    if color > 2 {
+        1
+    } else {
+        color
+    }
+}
+
+/// Bilinear demosaicing, interpolating every missing color from the nearest samples of
+/// that color. Works with any CFA pattern, including X-Trans.
+fn demosaic(data: &[f32], width: usize, height: usize, cfa: &CFA) -> Vec<[f32; 3]> {
+    let mut rgb = Vec::with_capacity(data.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let own = rgb_channel(cfa.color_at(y, x));
+            let mut pixel = [0.0; 3];
+            pixel[own] = data[y * width + x];
+
+            for radius in 1..=2usize {
+                let mut sums = [0.0; 3];
+                let mut counts = [0; 3];
+
+                for ny in y.saturating_sub(radius)..(y + radius + 1).min(height) {
+                    for nx in x.saturating_sub(radius)..(x + radius + 1).min(width) {
+                        let c = rgb_channel(cfa.color_at(ny, nx));
+
+                        sums[c] += data[ny * width + nx];
+                        counts[c] += 1;
+                    }
+                }
+
+                for c in (0..3).filter(|c| *c != own && counts[*c] != 0) {
+                    pixel[c] = sums[c] / counts[c] as f32;
+                }
+
+                if counts.iter().all(|count| *count != 0) {
+                    break;
+                }
+            }
+
+            rgb.push(pixel);
+        }
+    }
+
+    rgb
+}
+
+/// Gets white balance multipliers normalized to green, falling back to neutral daylight
+/// white balance when the camera didn't record one.
+fn white_balance(raw: &RawImage) -> [f32; 3] {
+    let valid = |wb: &[f32; 4]| wb[..3].iter().all(|c| c.is_finite() && *c > 0.0);
+
+    let wb = if valid(&raw.wb_coeffs) {
+        raw.wb_coeffs
+    } else {
+        raw.neutralwb()
+    };
+
+    if valid(&wb) {
+        [wb[0] / wb[1], 1.0, wb[2] / wb[1]]
+    } else {
+        [1.0; 3]
+    }
+}
+
+/// Builds the matrix converting white balanced camera colors to linear sRGB, normalized so
+/// white stays white. Falls back to identity for cameras without color data.
+fn camera_to_srgb(raw: &RawImage) -> [[f32; 3]; 3] {
+    const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    let mut srgb_to_camera = [[0.0; 3]; 4];
+
+    for (i, row) in srgb_to_camera.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3)
+                .map(|k| raw.xyz_to_cam[i][k] * SRGB_TO_XYZ[k][j])
+                .sum();
+        }
+
+        let sum: f32 = row.iter().sum();
+
+        if sum != 0.0 {
+            row.iter_mut().for_each(|value| *value /= sum);
+        }
+    }
+
+    if srgb_to_camera[..3]
+        .iter()
+        .flatten()
+        .all(|value| *value == 0.0)
+    {
+        return IDENTITY;
+    }
+
+    let camera_to_srgb = RawImage::pseudoinverse(srgb_to_camera);
+
+    let matrix = camera_to_srgb.map(|row| [row[0], row[1], row[2]]);
+
+    if matrix.iter().flatten().all(|value| value.is_finite()) {
+        matrix
+    } else {
+        IDENTITY
+    }
+}
+
+/// Applies the sRGB transfer function followed by a mild contrast curve.
+fn tone(linear: f32) -> f32 {
+    let linear = linear.clamp(0.0, 1.0);
+
+    let srgb = if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+
+    let smooth = srgb * srgb * (3.0 - 2.0 * srgb);
+
+    srgb + (smooth - srgb) * CONTRAST
+}
+
+#[inline]
+fn encode(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16
+}
+
+/// Rotates and flips the image as specified by the raw metadata.
+fn orient(image: DynamicImage, orientation: &Orientation) -> DynamicImage {
+    match orientation {
+        Orientation::HorizontalFlip => image.fliph(),
+        Orientation::Rotate180 => image.rotate180(),
+        Orientation::VerticalFlip => image.flipv(),
+        Orientation::Transpose => image.rotate90().fliph(),
+        Orientation::Rotate90 => image.rotate90(),
+        Orientation::Transverse => image.rotate270().fliph(),
+        Orientation::Rotate270 => image.rotate270(),
+        Orientation::Normal | Orientation::Unknown => image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bayer(width: usize, height: usize, rgb: [u16; 3]) -> RawImage {
+        let cfa = CFA::new("RGGB");
+
+        let data = (0..width * height)
+            .map(|i| rgb[cfa.color_at(i / width, i % width)])
+            .collect();
+
+        RawImage {
+            make: String::new(),
+            model: String::new(),
+            clean_make: String::new(),
+            clean_model: String::new(),
+            width,
+            height,
+            cpp: 1,
+            wb_coeffs: [1.0, 1.0, 1.0, f32::NAN],
+            whitelevels: [4095; 4],
+            blacklevels: [0; 4],
+            xyz_to_cam: [[0.0; 3]; 4],
+            cfa,
+            crops: [0; 4],
+            blackareas: vec![],
+            orientation: Orientation::Normal,
+            data: RawImageData::Integer(data),
+        }
+    }
+
+    #[test]
+    fn develop_bayer() {
+        let raw = bayer(8, 6, [4095, 0, 0]);
+        let image = develop(&raw).into_rgb16();
+
+        assert_eq!(image.dimensions(), (8, 6));
+        assert!(image.pixels().all(|p| p.0 == [u16::MAX, 0, 0]));
+    }
+
+    #[test]
+    fn develop_with_white_balance_and_levels() {
+        let mut raw = bayer(4, 4, [1024 + 512, 1024 + 1024, 1024 + 512]);
+        raw.blacklevels = [1024; 4];
+        raw.wb_coeffs = [2.0, 1.0, 2.0, f32::NAN];
+
+        let image = develop(&raw).into_rgb16();
+        let pixel = image.get_pixel(1, 1).0;
+
+        assert_eq!(pixel[0], pixel[1]);
+        assert_eq!(pixel[1], pixel[2]);
+    }
+
+    #[test]
+    fn develop_with_crop_and_orientation() {
+        let mut raw = bayer(10, 8, [4095, 4095, 4095]);
+        raw.crops = [2, 0, 0, 2];
+        raw.orientation = Orientation::Rotate90;
+
+        let image = develop(&raw);
+
+        assert_eq!((image.width(), image.height()), (6, 8));
+    }
+
+    #[test]
+    fn tone_curve() {
+        assert_eq!(encode(tone(0.0)), 0);
+        assert_eq!(encode(tone(1.0)), u16::MAX);
+        assert_eq!(encode(tone(2.0)), u16::MAX);
+        assert!(tone(0.18) > 0.4 && tone(0.18) < 0.5);
+    }
+
+    #[test]
+    fn neutral_color_matrix() {
+        let mut raw = bayer(2, 2, [0; 3]);
+        raw.xyz_to_cam = [
+            [0.6722, -0.0635, -0.0963],
+            [-0.4287, 1.2460, 0.2028],
+            [-0.0908, 0.2162, 0.5668],
+            [0.0, 0.0, 0.0],
+        ];
+
+        let matrix = camera_to_srgb(&raw);
+
+        for row in matrix {
+            assert!((row.iter().sum::<f32>() - 1.0).abs() < 1e-3);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use std::{error::Error, fs, io::Cursor};
 
 use super::*;
@@ -9,6 +11,8 @@ fn new_decoder() {
 
     assert_eq!(decoder.r.into_inner(), cursor);
     assert_eq!(decoder.format, None);
+    assert_eq!(decoder.extension, None);
+    assert_eq!(decoder.page, None);
     #[cfg(feature = "transform")]
     assert_eq!(decoder.fix_orientation, None);
 }
@@ -178,3 +182,298 @@ fn decode_webp() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn decode_from_bytes() -> Result<(), Box<dyn Error>> {
+    let bytes = fs::read("tests/files/png/f1t.png")?;
+
+    let decoder = Decoder::from_bytes(&bytes);
+    assert_eq!(decoder.format(), Some(ImageFormat::Png));
+
+    let image = decoder.decode()?;
+
+    assert_eq!(image.width(), 48);
+    assert_eq!(image.height(), 80);
+
+    Ok(())
+}
+
+#[test]
+fn decode_sniffs_format_without_hint() -> Result<(), Box<dyn Error>> {
+    let bytes = fs::read("tests/files/png/f1t.png")?;
+
+    let image = Decoder::new(Cursor::new(bytes)).decode()?;
+
+    assert_eq!(image.width(), 48);
+    assert_eq!(image.height(), 80);
+
+    Ok(())
+}
+
+#[test]
+fn decode_with_mismatched_extension() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("png_named.jpg");
+    fs::copy("tests/files/png/f1t.png", &path)?;
+
+    let decoder = Decoder::from_path(&path)?;
+
+    assert_eq!(decoder.format(), Some(ImageFormat::Png));
+    assert_eq!(
+        decoder.format_mismatch(),
+        Some((ImageFormat::Jpeg, ImageFormat::Png))
+    );
+
+    let image = decoder.decode()?;
+
+    assert_eq!(image.width(), 48);
+    assert_eq!(image.height(), 80);
+
+    Ok(())
+}
+
+#[test]
+fn decode_without_extension() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("no_extension");
+    fs::copy("tests/files/png/f1t.png", &path)?;
+
+    let decoder = Decoder::from_path(&path)?;
+
+    assert_eq!(decoder.format(), Some(ImageFormat::Png));
+    assert_eq!(decoder.format_mismatch(), None);
+
+    let image = decoder.decode()?;
+
+    assert_eq!(image.width(), 48);
+
+    Ok(())
+}
+
+#[test]
+fn decode_legacy_formats() -> Result<(), Box<dyn Error>> {
+    use image::{codecs::hdr::HdrEncoder, DynamicImage, ImageOutputFormat, RgbaImage};
+
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(16, 8, |x, y| {
+        image::Rgba([(x * 16) as u8, (y * 32) as u8, 128, 255])
+    }));
+
+    let formats = [
+        (ImageOutputFormat::Gif, ImageFormat::Gif),
+        (ImageOutputFormat::Bmp, ImageFormat::Bmp),
+        (ImageOutputFormat::Tga, ImageFormat::Tga),
+        (ImageOutputFormat::Ico, ImageFormat::Ico),
+        (ImageOutputFormat::Qoi, ImageFormat::Qoi),
+        (
+            ImageOutputFormat::Pnm(image::codecs::pnm::PnmSubtype::Pixmap(
+                image::codecs::pnm::SampleEncoding::Binary,
+            )),
+            ImageFormat::Pnm,
+        ),
+        (ImageOutputFormat::OpenExr, ImageFormat::OpenExr),
+    ];
+
+    for (output_format, format) in formats {
+        println!("format: {format:?}");
+
+        let mut bytes = Cursor::new(vec![]);
+
+        match format {
+            ImageFormat::OpenExr => DynamicImage::ImageRgba32F(image.to_rgba32f())
+                .write_to(&mut bytes, output_format)?,
+            ImageFormat::Pnm => {
+                DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut bytes, output_format)?
+            }
+            _ => image.write_to(&mut bytes, output_format)?,
+        }
+
+        let bytes = bytes.into_inner();
+
+        let decoder = match format {
+            ImageFormat::Tga => Decoder::new(Cursor::new(&bytes[..])).with_format(format),
+            _ => Decoder::from_bytes(&bytes),
+        };
+
+        assert_eq!(decoder.format(), Some(format));
+
+        let decoded = decoder.decode()?;
+
+        assert_eq!(decoded.width(), 16);
+        assert_eq!(decoded.height(), 8);
+    }
+
+    let mut bytes = vec![];
+    HdrEncoder::new(&mut bytes).encode(&vec![image::Rgb([4.0f32, 0.5, 0.0]); 16 * 8], 16, 8)?;
+
+    let decoder = Decoder::from_bytes(&bytes);
+    assert_eq!(decoder.format(), Some(ImageFormat::Hdr));
+
+    let decoded = decoder.decode()?;
+    assert_eq!(decoded.width(), 16);
+    assert_eq!(
+        decoded.as_rgb32f().unwrap().get_pixel(0, 0).0,
+        [4.0, 0.5, 0.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn decode_multi_page_tiff() -> Result<(), Box<dyn Error>> {
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("pages.tiff");
+
+    let mut encoder = TiffEncoder::new(fs::File::create(&path)?)?;
+    encoder.write_image::<colortype::Gray8>(4, 2, &[0; 8])?;
+    encoder.write_image::<colortype::RGB8>(2, 2, &[255; 12])?;
+    drop(encoder);
+
+    let pages = Decoder::from_path(&path)?.decode_pages()?;
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].color(), image::ColorType::L8);
+    assert_eq!(pages[1].color(), image::ColorType::Rgb8);
+
+    let page = Decoder::from_path(&path)?.with_page(1).decode()?;
+    assert_eq!((page.width(), page.height()), (2, 2));
+
+    let missing = Decoder::from_path(&path)?.with_page(2).decode();
+
+    assert!(missing.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn decode_ico_sub_images() -> Result<(), Box<dyn Error>> {
+    use image::codecs::ico::{IcoEncoder, IcoFrame};
+
+    let frames = [32, 16].map(|size| {
+        IcoFrame::as_png(
+            &vec![0; size * size * 4],
+            size as u32,
+            size as u32,
+            image::ColorType::Rgba8,
+        )
+    });
+
+    let mut bytes = vec![];
+    IcoEncoder::new(&mut bytes)
+        .encode_images(&frames.into_iter().collect::<Result<Vec<_>, _>>()?)?;
+
+    // without a page the largest sub-image is decoded
+    assert_eq!(Decoder::from_bytes(&bytes).decode()?.width(), 32);
+    assert_eq!(
+        Decoder::from_bytes(&bytes).with_page(1).decode()?.width(),
+        16
+    );
+    assert_eq!(Decoder::from_bytes(&bytes).decode_pages()?.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn decode_tga_from_path() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("legacy.tga");
+
+    image::RgbImage::from_pixel(4, 2, image::Rgb([255, 0, 0])).save(&path)?;
+
+    let decoder = Decoder::from_path(&path)?;
+    assert_eq!(decoder.format(), Some(ImageFormat::Tga));
+
+    let image = decoder.decode()?;
+
+    assert_eq!(image.width(), 4);
+    assert_eq!(image.height(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn decode_svg() -> Result<(), Box<dyn Error>> {
+    let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="2">
+        <rect width="2" height="2" fill="#ff0000"/>
+    </svg>"##;
+
+    let decoder = Decoder::from_bytes(svg);
+    assert_eq!(decoder.format(), Some(ImageFormat::Svg));
+
+    let image = decoder.decode()?.into_rgba8();
+
+    assert_eq!(image.dimensions(), (4, 2));
+    assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(3, 1).0, [0, 0, 0, 0]);
+
+    let image = Decoder::from_bytes(svg).with_dpi(48.0).decode()?;
+    assert_eq!((image.width(), image.height()), (2, 1));
+
+    let invalid = Decoder::from_bytes(svg).with_dpi(0.0).decode();
+    assert!(matches!(invalid, Err(ImageError::Parameter(_))));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "svg", feature = "resizing"))]
+fn decode_svg_at_target_size() -> Result<(), Box<dyn Error>> {
+    use crate::config::ResizeConfig;
+
+    let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 4 2">
+        <rect width="2" height="2" fill="#00ff00"/>
+    </svg>"##;
+
+    let config = ResizeConfig::default().with_width(400);
+    let image = Decoder::from_bytes(svg)
+        .with_resize_config(config)
+        .decode()?
+        .into_rgba8();
+
+    assert_eq!(image.dimensions(), (400, 200));
+    assert_eq!(config.dimensions(400, 200), (400, 200));
+
+    // the edge is rendered sharp instead of upscaled from the default size
+    assert_eq!(image.get_pixel(199, 100).0, [0, 255, 0, 255]);
+    assert_eq!(image.get_pixel(200, 100).0, [0, 0, 0, 0]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "svg")]
+fn decode_svg_from_path() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("icon.svg");
+
+    fs::write(
+        &path,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"><circle cx="12" cy="12" r="10"/></svg>"#,
+    )?;
+
+    let decoder = Decoder::from_path(&path)?;
+    assert_eq!(decoder.format(), Some(ImageFormat::Svg));
+
+    let image = decoder.decode()?;
+
+    assert_eq!((image.width(), image.height()), (24, 24));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "raw")]
+fn raw_extension_overrides_tiff_content() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("camera.dng");
+
+    image::RgbImage::new(4, 4).save_with_format(&path, image::ImageFormat::Tiff)?;
+
+    let decoder = Decoder::from_path(&path)?;
+
+    assert_eq!(decoder.format(), Some(ImageFormat::Raw));
+    assert_eq!(decoder.format_mismatch(), None);
+
+    Ok(())
+}
@@ -4,22 +4,31 @@ use image::{
     ImageResult,
 };
 use rgb::FromSlice;
-use std::io::{Seek, Write};
+use std::io::{Cursor, Seek, Write};
 
 use crate::config::EncoderConfig;
 use crate::config::ResizeType;
+use crate::operations::tone_mapping::tone_map;
+use crate::Image;
+
+pub use progress::EncodingStage;
 
 #[cfg(feature = "libjxl")]
 mod libjxl;
+mod progress;
+
+/// Callback receiving encoding stages.
+type ProgressCallback = Box<dyn FnMut(EncodingStage) + Send>;
 
 /// A struct for encoding images using various codecs.
-pub struct Encoder<W: Write + Seek> {
+pub struct Encoder<W: Write> {
     w: W,
     data: DynamicImage,
     conf: EncoderConfig,
+    progress: Option<ProgressCallback>,
 }
 
-impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
+impl<W: Write> Encoder<W> {
     /// Creates a new [`Encoder`] instance with the specified writer and image data.
     ///
     /// # Parameters
@@ -47,6 +56,7 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
             w,
             data,
             conf: EncoderConfig::default(),
+            progress: None,
         }
     }
 
@@ -89,6 +99,37 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
         self
     }
 
+    /// Sets the callback receiving stages of encoding, so slow encodes can report progress.
+    ///
+    /// # Parameters
+    ///
+    /// - `callback`: The function called with every [`EncodingStage`] as it begins.
+    ///
+    /// # Returns
+    ///
+    /// Returns a modified [`Encoder`] instance with the progress callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::{Encoder, EncodingStage};
+    /// use image::{DynamicImage, RgbImage};
+    ///
+    /// let image = DynamicImage::ImageRgb8(RgbImage::new(800, 600));
+    ///
+    /// let mut buf = vec![];
+    ///
+    /// Encoder::new(&mut buf, image)
+    ///     .with_progress(|stage| println!("{stage}"))
+    ///     .encode()?;
+    /// # Ok::<(), image::ImageError>(())
+    /// ```
+    #[inline]
+    pub fn with_progress(mut self, callback: impl FnMut(EncodingStage) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
     /// Encodes the image using the configured settings.
     ///
     /// # Returns
@@ -116,25 +157,33 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
     /// # fs::remove_file("output.jpg")?;
     /// # Ok::<(), image::ImageError>(())
     /// ```
-    #[allow(unused_mut)]
     pub fn encode(mut self) -> ImageResult<()> {
+        let mut progress = self.progress.take();
+        let mut last = None;
+
+        // quantized images are already compressing when they fall through to the codec
+        let mut report = |stage| {
+            if let Some(callback) = progress.as_mut().filter(|_| last != Some(stage)) {
+                callback(stage);
+            }
+
+            last = Some(stage);
+        };
+
+        self.encode_stages(&mut report)?;
+        report(EncodingStage::Finished);
+
+        Ok(())
+    }
+
+    #[allow(unused_mut)]
+    fn encode_stages(mut self, report: &mut dyn FnMut(EncodingStage)) -> ImageResult<()> {
         // TODO: Move resize out from encoder to operations
         #[cfg(feature = "resizing")]
         if let Some(resize_config) = self.conf.resize_config() {
-            let aspect_ratio = self.data.width() as f64 / self.data.height() as f64;
+            report(EncodingStage::Resizing);
 
-            let width = resize_config.width().unwrap_or(
-                resize_config
-                    .height()
-                    .map(|h| (h as f64 * aspect_ratio) as usize)
-                    .unwrap_or(self.data.width() as usize),
-            );
-            let height = resize_config.height().unwrap_or(
-                resize_config
-                    .width()
-                    .map(|w| (w as f64 / aspect_ratio) as usize)
-                    .unwrap_or(self.data.height() as usize),
-            );
+            let (width, height) = resize_config.dimensions(self.data.width(), self.data.height());
 
             let filter = match resize_config.filter_type() {
                 ResizeType::Point => image::imageops::Nearest,
@@ -144,67 +193,65 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
                 ResizeType::Lanczos3 => image::imageops::Lanczos3,
             };
 
-            self.data = self.data.resize(width as u32, height as u32, filter);
+            self.data = self.data.resize(width, height, filter);
         }
 
-        // TODO: Move quantization out from encoder to operations
-        #[cfg(feature = "quantization")]
-        if let Some(quantization_config) = self.conf.quantization_config() {
-            let mut image = self.data.to_rgba8();
-
-            let pixels = image.as_raw();
-
-            let mut liq = imagequant::new();
-
-            liq.set_quality(0, quantization_config.quality())
-                .map_err(|e| {
-                    ImageError::Encoding(EncodingError::new(
-                        ImageFormatHint::Name("Quantization".to_string()),
-                        e,
-                    ))
-                })?;
-
-            let mut img = liq
-                .new_image_borrowed(
-                    pixels.as_rgba(),
-                    image.width() as usize,
-                    image.height() as usize,
-                    0.0,
-                )
-                .map_err(|e| {
-                    ImageError::Encoding(EncodingError::new(
-                        ImageFormatHint::Name("Quantization".to_string()),
-                        e,
-                    ))
-                })?;
-
-            let mut res = liq.quantize(&mut img).map_err(|e| {
-                ImageError::Encoding(EncodingError::new(
-                    ImageFormatHint::Name("Quantization".to_string()),
-                    e,
-                ))
-            })?;
+        // Floating point images from HDR and OpenEXR are tone mapped for SDR codecs
+        if !self.conf.codec().supports_hdr() {
+            if matches!(
+                self.data,
+                DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+            ) {
+                report(EncodingStage::ToneMapping);
+            }
 
-            res.set_dithering_level(quantization_config.dithering_level())
-                .map_err(|e| {
-                    ImageError::Encoding(EncodingError::new(
-                        ImageFormatHint::Name("Quantization".to_string()),
-                        e,
-                    ))
-                })?;
+            self.data = tone_map(self.data, self.conf.tone_mapping());
+        }
 
-            let (palette, pixels) = res.remapped(&mut img).map_err(|e| {
-                ImageError::Encoding(EncodingError::new(
-                    ImageFormatHint::Name("Quantization".to_string()),
-                    e,
-                ))
-            })?;
+        if !self.conf.codec().supports_alpha() && self.data.color().has_alpha() {
+            report(EncodingStage::Flattening);
+
+            let grayscale = !self.data.color().has_color();
+
+            let mut image = Image::from(self.data);
+            image.flatten(self.conf.background());
+
+            let image = DynamicImage::from(image);
+
+            self.data = if grayscale {
+                DynamicImage::ImageLuma8(image.into_luma8())
+            } else {
+                DynamicImage::ImageRgb8(image.into_rgb8())
+            };
+        }
+
+        // TODO: Move quantization out from encoder to operations
+        #[cfg(feature = "quantization")]
+        if self.conf.quantization_config().is_some()
+            || self.conf.palette().is_some()
+            || self.conf.codec() == &crate::config::Codec::Gif
+        {
+            report(EncodingStage::Quantizing);
+
+            let (palette, indices) = self.quantize()?;
+
+            report(EncodingStage::Compressing);
+
+            match self.conf.codec() {
+                crate::config::Codec::Png => return self.encode_indexed_png(&palette, &indices),
+                crate::config::Codec::Gif => return self.encode_indexed_gif(&palette, &indices),
+                #[cfg(feature = "oxipng")]
+                crate::config::Codec::OxiPng => {
+                    return self.encode_indexed_oxipng(palette, indices)
+                }
+                _ => {}
+            }
 
             self.data = DynamicImage::ImageRgba8(
                 ImageBuffer::from_raw(
-                    image.width(),
-                    image.height(),
-                    pixels
+                    self.data.width(),
+                    self.data.height(),
+                    indices
                         .iter()
                         .flat_map(|pix| palette[*pix as usize].iter())
                         .collect::<Vec<u8>>(),
@@ -215,6 +262,8 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
             );
         }
 
+        report(EncodingStage::Compressing);
+
         match self.conf.codec() {
             crate::config::Codec::MozJpeg => self.encode_mozjpeg(),
             crate::config::Codec::Png => self.encode_png(),
@@ -226,7 +275,281 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
             crate::config::Codec::WebP => self.encode_webp(),
             #[cfg(feature = "avif")]
             crate::config::Codec::Avif => self.encode_avif(),
+            crate::config::Codec::Tiff => self.encode_tiff(),
+            crate::config::Codec::Gif => self.encode_gif(),
+            crate::config::Codec::Qoi => self.encode_qoi(),
+            crate::config::Codec::Bmp => self.encode_bmp(),
+        }
+    }
+
+    /// Quantizes the image data, returning the palette and per-pixel palette indices.
+    ///
+    /// Remaps onto the configured fixed palette if there is one.
+    #[cfg(feature = "quantization")]
+    fn quantize(&self) -> ImageResult<(Vec<rgb::RGBA8>, Vec<u8>)> {
+        let quantization_error = |e: imagequant::Error| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Name("Quantization".to_string()),
+                e,
+            ))
+        };
+
+        let quantization_config = self.conf.quantization_config().copied().unwrap_or_default();
+
+        let image = self.data.to_rgba8();
+
+        let pixels = image.as_raw().as_rgba();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+
+        match self.conf.palette() {
+            Some(palette) => palette.remap(pixels, width, height, &quantization_config),
+            None => crate::config::quantize(pixels, width, height, &quantization_config),
+        }
+        .map_err(quantization_error)
+    }
+
+    /// Writes a palette PNG with the smallest bit depth that fits the palette.
+    #[cfg(feature = "quantization")]
+    fn encode_indexed_png(mut self, palette: &[rgb::RGBA8], indices: &[u8]) -> ImageResult<()> {
+        let png_error = |e: png::EncodingError| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Png),
+                e,
+            ))
+        };
+
+        let width = self.data.width();
+        let height = self.data.height();
+
+        let (palette, indices) = transparent_first(palette, indices);
+
+        let bit_depth = match palette.len() {
+            0..=2 => png::BitDepth::One,
+            3..=4 => png::BitDepth::Two,
+            5..=16 => png::BitDepth::Four,
+            _ => png::BitDepth::Eight,
+        };
+
+        let mut encoder = png::Encoder::new(&mut self.w, width, height);
+
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(bit_depth);
+        encoder.set_compression(png::Compression::Best);
+        encoder.set_filter(png::FilterType::NoFilter);
+        encoder.set_palette(
+            palette
+                .iter()
+                .flat_map(|c| [c.r, c.g, c.b])
+                .collect::<Vec<u8>>(),
+        );
+
+        let trns = palette
+            .iter()
+            .take_while(|c| c.a < 255)
+            .map(|c| c.a)
+            .collect::<Vec<u8>>();
+
+        if !trns.is_empty() {
+            encoder.set_trns(trns);
+        }
+
+        let mut writer = encoder.write_header().map_err(png_error)?;
+
+        writer
+            .write_image_data(&pack_indices(&indices, width as usize, bit_depth as u8))
+            .map_err(png_error)?;
+
+        writer.finish().map_err(png_error)?;
+
+        Ok(())
+    }
+
+    /// Writes a GIF frame with the palette, pixels with alpha below 128 become transparent.
+    #[cfg(feature = "quantization")]
+    fn encode_indexed_gif(mut self, palette: &[rgb::RGBA8], indices: &[u8]) -> ImageResult<()> {
+        let gif_error = |e: gif::EncodingError| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Gif),
+                e,
+            ))
+        };
+
+        let width = u16::try_from(self.data.width());
+        let height = u16::try_from(self.data.height());
+
+        let (Ok(width), Ok(height)) = (width, height) else {
+            return Err(ImageError::Unsupported(
+                UnsupportedError::from_format_and_kind(
+                    ImageFormatHint::Exact(ImageFormat::Gif),
+                    UnsupportedErrorKind::GenericFeature(
+                        "Image dimensions larger than 65535".to_string(),
+                    ),
+                ),
+            ));
+        };
+
+        // GIF supports a single fully transparent color
+        let transparent = palette
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.a < 128)
+            .min_by_key(|(_, c)| c.a)
+            .map(|(i, _)| i as u8);
+
+        let buffer = match transparent {
+            Some(transparent) => indices
+                .iter()
+                .map(|i| {
+                    if palette[*i as usize].a < 128 {
+                        transparent
+                    } else {
+                        *i
+                    }
+                })
+                .collect(),
+            None => indices.to_vec(),
+        };
+
+        let frame = gif::Frame {
+            width,
+            height,
+            buffer: buffer.into(),
+            transparent,
+            ..Default::default()
+        };
+
+        let mut encoder = gif::Encoder::new(
+            &mut self.w,
+            width,
+            height,
+            &palette
+                .iter()
+                .flat_map(|c| [c.r, c.g, c.b])
+                .collect::<Vec<u8>>(),
+        )
+        .map_err(gif_error)?;
+
+        encoder.write_frame(&frame).map_err(gif_error)
+    }
+
+    fn encode_gif(mut self) -> ImageResult<()> {
+        let image = self.data.to_rgba8();
+
+        image::codecs::gif::GifEncoder::new(&mut self.w).encode(
+            image.as_raw(),
+            image.width(),
+            image.height(),
+            ColorType::Rgba8,
+        )
+    }
+
+    fn encode_tiff(mut self) -> ImageResult<()> {
+        use tiff::encoder::colortype;
+
+        let tiff_error = |e: tiff::TiffError| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Tiff),
+                e,
+            ))
+        };
+
+        let width = self.data.width();
+        let height = self.data.height();
+        let compression = self.conf.tiff_compression();
+
+        // TIFF encoder has no gray with alpha color types
+        let data = match self.data {
+            DynamicImage::ImageLumaA8(_) => DynamicImage::ImageRgba8(self.data.to_rgba8()),
+            DynamicImage::ImageLumaA16(_) => DynamicImage::ImageRgba16(self.data.to_rgba16()),
+            data => data,
+        };
+
+        // TIFF encoder seeks back to write offsets, so the image is buffered in memory
+        let mut buf = Cursor::new(Vec::new());
+        let mut encoder = tiff::encoder::TiffEncoder::new(&mut buf).map_err(tiff_error)?;
+
+        match data {
+            DynamicImage::ImageLuma8(image) => write_tiff::<_, colortype::Gray8>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageRgb8(image) => write_tiff::<_, colortype::RGB8>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageLuma16(image) => write_tiff::<_, colortype::Gray16>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageRgb16(image) => write_tiff::<_, colortype::RGB16>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageRgba16(image) => write_tiff::<_, colortype::RGBA16>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageRgb32F(image) => write_tiff::<_, colortype::RGB32Float>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            DynamicImage::ImageRgba32F(image) => write_tiff::<_, colortype::RGBA32Float>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                image.as_raw(),
+            ),
+            data => write_tiff::<_, colortype::RGBA8>(
+                &mut encoder,
+                compression,
+                width,
+                height,
+                data.to_rgba8().as_raw(),
+            ),
         }
+        .map_err(tiff_error)?;
+
+        self.w.write_all(buf.get_ref())?;
+
+        Ok(())
+    }
+
+    fn encode_qoi(mut self) -> ImageResult<()> {
+        let data = match self.data.color().has_alpha() {
+            true => DynamicImage::ImageRgba8(self.data.into_rgba8()),
+            false => DynamicImage::ImageRgb8(self.data.into_rgb8()),
+        };
+
+        data.write_with_encoder(image::codecs::qoi::QoiEncoder::new(&mut self.w))
+    }
+
+    fn encode_bmp(mut self) -> ImageResult<()> {
+        let data = match self.data.color() {
+            ColorType::L8 | ColorType::La8 | ColorType::Rgb8 | ColorType::Rgba8 => self.data,
+            color if color.has_alpha() => DynamicImage::ImageRgba8(self.data.into_rgba8()),
+            _ => DynamicImage::ImageRgb8(self.data.into_rgb8()),
+        };
+
+        data.write_with_encoder(image::codecs::bmp::BmpEncoder::new(&mut self.w))
     }
 
     fn encode_mozjpeg(self) -> ImageResult<()> {
@@ -234,7 +557,8 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
         let height = self.data.height();
         let quality = self.conf.quality();
 
-        std::panic::catch_unwind(|| -> ImageResult<()> {
+        // mozjpeg reports errors by panicking, the writer isn't used after that
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> ImageResult<()> {
             let format = match self.data.color() {
                 ColorType::L8 | ColorType::L16 => mozjpeg::ColorSpace::JCS_GRAYSCALE,
                 ColorType::La8 | ColorType::La16 => mozjpeg::ColorSpace::JCS_GRAYSCALE,
@@ -248,9 +572,11 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
             };
 
             let data = match self.data.color() {
-                ColorType::La8 | ColorType::La16 => {
+                ColorType::La8 | ColorType::La16 | ColorType::L16 => {
                     DynamicImage::ImageLuma8(self.data.into_luma8())
                 }
+                ColorType::Rgb16 => DynamicImage::ImageRgb8(self.data.into_rgb8()),
+                ColorType::Rgba16 => DynamicImage::ImageRgba8(self.data.into_rgba8()),
                 _ => self.data,
             };
 
@@ -267,7 +593,7 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
             comp.finish()?;
 
             Ok(())
-        })
+        }))
         .map_err(|_| {
             ImageError::Encoding(EncodingError::from_format_hint(ImageFormatHint::Exact(
                 ImageFormat::Jpeg,
@@ -276,7 +602,8 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
     }
 
     fn encode_png(mut self) -> ImageResult<()> {
-        self.data.write_to(&mut self.w, ImageFormat::Png)
+        self.data
+            .write_with_encoder(image::codecs::png::PngEncoder::new(&mut self.w))
     }
 
     #[cfg(all(feature = "jxl",not(feature = "libjxl")))]
@@ -325,7 +652,7 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
     }
 
     #[cfg(feature = "libjxl")]
-    fn encode_jpegxl(self) -> ImageResult<()> {
+    fn encode_jpegxl(mut self) -> ImageResult<()> {
         use libjxl::LibJxlEncoder;
 
         let mut encoder = LibJxlEncoder::new();
@@ -336,13 +663,18 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
                 LibJxlEncoder::DistanceFromQuality(self.conf.quality()));
         }
 
-        encoder.encode(self.w, self.data).map_err(|e| {
+        // libjxl seeks in its output, so the image is buffered in memory
+        let mut buf = Cursor::new(Vec::new());
+
+        encoder.encode(&mut buf, self.data).map_err(|e| {
             ImageError::Encoding(EncodingError::new(
                 ImageFormatHint::Name("JpegXL".to_string()),
                 e,
             ))
         })?;
 
+        self.w.write_all(buf.get_ref())?;
+
         Ok(())
     }
 
@@ -415,6 +747,36 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
         Ok(())
     }
 
+    #[cfg(all(feature = "oxipng", feature = "quantization"))]
+    fn encode_indexed_oxipng(
+        mut self,
+        palette: Vec<rgb::RGBA8>,
+        indices: Vec<u8>,
+    ) -> ImageResult<()> {
+        let oxipng_error = |e: oxipng::PngError| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Png),
+                e,
+            ))
+        };
+
+        let img = oxipng::RawImage::new(
+            self.data.width(),
+            self.data.height(),
+            oxipng::ColorType::Indexed { palette },
+            oxipng::BitDepth::Eight,
+            indices,
+        )
+        .map_err(oxipng_error)?;
+
+        self.w.write_all(
+            &img.create_optimized_png(&oxipng::Options::default())
+                .map_err(oxipng_error)?,
+        )?;
+
+        Ok(())
+    }
+
     #[cfg(feature = "webp")]
     fn encode_webp(self) -> ImageResult<()> {
         use image::codecs::webp::WebPQuality;
@@ -459,5 +821,74 @@ impl<W: Write + Seek + std::panic::UnwindSafe> Encoder<W> {
     }
 }
 
+/// Writes a TIFF image with the specified compression.
+fn write_tiff<W, C>(
+    encoder: &mut tiff::encoder::TiffEncoder<W>,
+    compression: crate::config::TiffCompression,
+    width: u32,
+    height: u32,
+    data: &[C::Inner],
+) -> tiff::TiffResult<()>
+where
+    W: Write + Seek,
+    C: tiff::encoder::colortype::ColorType,
+    [C::Inner]: tiff::encoder::TiffValue,
+{
+    use tiff::encoder::compression::{Deflate, DeflateLevel, Lzw, Uncompressed};
+
+    match compression {
+        crate::config::TiffCompression::None => {
+            encoder.write_image_with_compression::<C, _>(width, height, Uncompressed, data)
+        }
+        crate::config::TiffCompression::Lzw => {
+            encoder.write_image_with_compression::<C, _>(width, height, Lzw, data)
+        }
+        crate::config::TiffCompression::Deflate => encoder.write_image_with_compression::<C, _>(
+            width,
+            height,
+            Deflate::with_level(DeflateLevel::Best),
+            data,
+        ),
+    }
+}
+
+/// Reorders the palette so that all transparent colors come first, which keeps the `tRNS` chunk short.
+#[cfg(feature = "quantization")]
+fn transparent_first(palette: &[rgb::RGBA8], indices: &[u8]) -> (Vec<rgb::RGBA8>, Vec<u8>) {
+    let mut order: Vec<usize> = (0..palette.len()).collect();
+    order.sort_by_key(|&i| palette[i].a == 255);
+
+    let mut remap = [0u8; 256];
+    for (new, &old) in order.iter().enumerate() {
+        remap[old] = new as u8;
+    }
+
+    (
+        order.iter().map(|&i| palette[i]).collect(),
+        indices.iter().map(|&i| remap[i as usize]).collect(),
+    )
+}
+
+/// Packs 8-bit palette indices into rows of `bit_depth` bits per pixel, padding every row to a full byte.
+#[cfg(feature = "quantization")]
+fn pack_indices(indices: &[u8], width: usize, bit_depth: u8) -> Vec<u8> {
+    if bit_depth == 8 {
+        return indices.to_vec();
+    }
+
+    let pixels_per_byte = (8 / bit_depth) as usize;
+
+    indices
+        .chunks(width)
+        .flat_map(|row| {
+            row.chunks(pixels_per_byte).map(|pixels| {
+                pixels.iter().enumerate().fold(0u8, |byte, (i, &index)| {
+                    byte | index << (8 - bit_depth as usize * (i + 1))
+                })
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests;
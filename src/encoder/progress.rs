@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// Stage of encoding reported to the callback set with
+/// [`Encoder::with_progress`](super::Encoder::with_progress).
+///
+/// Stages are reported as they begin, stages that don't apply to the image are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::EncodingStage;
+///
+/// assert_eq!(EncodingStage::ToneMapping.to_string(), "tone mapping");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodingStage {
+    /// Resizing the image to the configured size.
+    Resizing,
+
+    /// Tone mapping floating point image for codec without HDR support.
+    ToneMapping,
+
+    /// Flattening transparent image onto background for codec without alpha support.
+    Flattening,
+
+    /// Quantizing the image or remapping it onto the palette.
+    Quantizing,
+
+    /// Compressing the image with the codec and writing it.
+    Compressing,
+
+    /// The image is encoded.
+    Finished,
+}
+
+impl fmt::Display for EncodingStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Resizing => "resizing",
+            Self::ToneMapping => "tone mapping",
+            Self::Flattening => "flattening",
+            Self::Quantizing => "quantizing",
+            Self::Compressing => "compressing",
+            Self::Finished => "finished",
+        })
+    }
+}
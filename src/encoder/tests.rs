@@ -3,7 +3,7 @@ use std::io::Cursor;
 
 // Import the necessary dependencies from the code
 use super::*;
-use crate::config::Codec;
+use crate::config::{Background, Codec, ToneMapping, ToneMappingConfig};
 
 #[test]
 fn encoder_new() {
@@ -21,6 +21,33 @@ fn encoder_new() {
     assert_eq!(encoder.conf.codec(), &Codec::MozJpeg);
 }
 
+#[test]
+fn encode_flattens_alpha_for_jpeg() {
+    let image = RgbaImage::from_pixel(16, 16, image::Rgba([0, 0, 0, 0]));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("flattens_alpha.jpg");
+
+    let config = EncoderConfig::new(Codec::MozJpeg)
+        .with_quality(100.0)
+        .unwrap()
+        .with_background(Background::Color(rgb::RGB8::new(255, 0, 0)));
+
+    Encoder::new(
+        std::fs::File::create(&path).unwrap(),
+        DynamicImage::ImageRgba8(image),
+    )
+    .with_config(config)
+    .encode()
+    .unwrap();
+
+    let decoded = image::open(&path).unwrap().into_rgb8();
+
+    assert_eq!(decoded.dimensions(), (16, 16));
+    assert!(decoded
+        .pixels()
+        .all(|p| p[0] > 240 && p[1] < 15 && p[2] < 15));
+}
+
 #[test]
 fn encoder_with_config() {
     // Create a mock image and writer
@@ -40,3 +67,398 @@ fn encoder_with_config() {
     assert_eq!(encoder.conf.codec(), &Codec::MozJpeg);
     assert_eq!(encoder.conf.quality(), 90.0);
 }
+
+#[test]
+#[cfg(feature = "quantization")]
+fn pack_indices_to_bit_depth() {
+    let indices = [1, 0, 1, 1, 0, 0, 0, 1, 1, 1];
+
+    assert_eq!(pack_indices(&indices, 5, 1), vec![0b1011_0000, 0b0011_1000]);
+    assert_eq!(
+        pack_indices(&[3, 2, 1, 0, 1], 5, 2),
+        vec![0b1110_0100, 0b0100_0000]
+    );
+    assert_eq!(pack_indices(&[15, 1, 2], 3, 4), vec![0xf1, 0x20]);
+    assert_eq!(pack_indices(&[200, 1], 2, 8), vec![200, 1]);
+}
+
+#[test]
+#[cfg(feature = "quantization")]
+fn transparent_colors_first() {
+    let palette = [
+        rgb::RGBA8::new(255, 0, 0, 255),
+        rgb::RGBA8::new(0, 0, 0, 0),
+        rgb::RGBA8::new(0, 255, 0, 255),
+        rgb::RGBA8::new(0, 0, 255, 128),
+    ];
+
+    let (palette, indices) = transparent_first(&palette, &[0, 1, 2, 3]);
+
+    assert_eq!(
+        palette,
+        vec![
+            rgb::RGBA8::new(0, 0, 0, 0),
+            rgb::RGBA8::new(0, 0, 255, 128),
+            rgb::RGBA8::new(255, 0, 0, 255),
+            rgb::RGBA8::new(0, 255, 0, 255),
+        ]
+    );
+    assert_eq!(indices, vec![2, 0, 3, 1]);
+}
+
+#[test]
+#[cfg(feature = "quantization")]
+fn encode_quantized_png_as_indexed() {
+    use crate::config::QuantizationConfig;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("quantized_png.png");
+
+    let mut image = RgbaImage::new(8, 4);
+    for (x, _, pixel) in image.enumerate_pixels_mut() {
+        *pixel = match x % 4 {
+            0 => image::Rgba([255, 0, 0, 255]),
+            1 => image::Rgba([0, 255, 0, 255]),
+            2 => image::Rgba([0, 0, 255, 255]),
+            _ => image::Rgba([0, 0, 0, 0]),
+        };
+    }
+
+    let config = EncoderConfig::new(Codec::Png).with_quantization(QuantizationConfig::new());
+
+    Encoder::new(
+        std::fs::File::create(&path).unwrap(),
+        DynamicImage::ImageRgba8(image.clone()),
+    )
+    .with_config(config)
+    .encode()
+    .unwrap();
+
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let reader = decoder.read_info().unwrap();
+    let info = reader.info();
+
+    assert_eq!(info.color_type, png::ColorType::Indexed);
+    assert_eq!(info.bit_depth, png::BitDepth::Two);
+    assert_eq!(info.palette.as_ref().unwrap().len(), 4 * 3);
+    assert_eq!(info.trns.as_deref(), Some(&[0u8][..]));
+
+    let decoded = image::open(&path).unwrap().into_rgba8();
+
+    // color of fully transparent pixels is not preserved
+    for (decoded, original) in decoded.pixels().zip(image.pixels()) {
+        if original[3] == 0 {
+            assert_eq!(decoded[3], 0);
+        } else {
+            assert_eq!(decoded, original);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "quantization")]
+fn encode_with_shared_palette() {
+    use crate::config::Palette;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shared_palette.png");
+
+    let black = rgb::RGBA8::new(0, 0, 0, 255);
+    let white = rgb::RGBA8::new(255, 255, 255, 255);
+
+    let image = RgbaImage::from_fn(8, 8, |x, _| {
+        if x < 4 {
+            image::Rgba([30, 30, 30, 255])
+        } else {
+            image::Rgba([220, 220, 220, 255])
+        }
+    });
+
+    let config =
+        EncoderConfig::new(Codec::Png).with_palette(Palette::new(vec![black, white]).unwrap());
+
+    Encoder::new(
+        std::fs::File::create(&path).unwrap(),
+        DynamicImage::ImageRgba8(image),
+    )
+    .with_config(config)
+    .encode()
+    .unwrap();
+
+    let decoded = image::open(&path).unwrap().into_rgba8();
+
+    for (x, _, pixel) in decoded.enumerate_pixels() {
+        let expected = if x < 4 { black } else { white };
+        assert_eq!(pixel.0, [expected.r, expected.g, expected.b, expected.a]);
+    }
+}
+
+#[test]
+fn encode_float_image() {
+    let image = DynamicImage::ImageRgb32F(image::Rgb32FImage::from_pixel(
+        8,
+        4,
+        image::Rgb([0.5, 2.0, -1.0]),
+    ));
+
+    for codec in [Codec::MozJpeg, Codec::Png] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("float.{}", codec.to_extension()));
+
+        let tone_mapping = ToneMappingConfig::new(ToneMapping::Clip);
+
+        Encoder::new(std::fs::File::create(&path).unwrap(), image.clone())
+            .with_config(EncoderConfig::new(codec).with_tone_mapping(tone_mapping))
+            .encode()
+            .unwrap();
+
+        let decoded = image::open(&path).unwrap().into_rgb8();
+
+        assert_eq!(decoded.dimensions(), (8, 4));
+        assert_eq!(decoded.get_pixel(0, 0)[1], 255);
+        assert_eq!(decoded.get_pixel(0, 0)[2], 0);
+    }
+}
+
+#[test]
+fn encode_tone_mapped_image() {
+    let image = DynamicImage::ImageRgba32F(image::Rgba32FImage::from_pixel(
+        4,
+        4,
+        image::Rgba([0.5, 1.0, 4.0, 0.5]),
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tone_mapped.png");
+
+    Encoder::new(std::fs::File::create(&path).unwrap(), image)
+        .with_config(EncoderConfig::new(Codec::Png))
+        .encode()
+        .unwrap();
+
+    let decoded = image::open(&path).unwrap();
+
+    let pixel = decoded.as_rgba8().unwrap().get_pixel(0, 0).0;
+
+    assert!(pixel[0] < pixel[1] && pixel[1] < pixel[2]);
+    assert!(pixel[2] < 255);
+    assert_eq!(pixel[3], 128);
+}
+
+#[test]
+fn encode_hdr_tiff() {
+    let image = DynamicImage::ImageRgb32F(image::Rgb32FImage::from_pixel(
+        4,
+        4,
+        image::Rgb([0.5, 2.0, 16.0]),
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("hdr.tiff");
+
+    Encoder::new(std::fs::File::create(&path).unwrap(), image)
+        .with_config(EncoderConfig::new(Codec::Tiff))
+        .encode()
+        .unwrap();
+
+    let mut decoder = tiff::decoder::Decoder::new(std::fs::File::open(&path).unwrap()).unwrap();
+    let decoded = decoder.read_image().unwrap();
+
+    match decoded {
+        tiff::decoder::DecodingResult::F32(data) => assert_eq!(data[..3], [0.5, 2.0, 16.0]),
+        _ => panic!("HDR image wasn't stored as floating point TIFF"),
+    }
+}
+
+#[test]
+fn encode_16_bit_jpeg() {
+    let images = [
+        DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(
+            8,
+            4,
+            image::Rgb([u16::MAX, 0, 0]),
+        )),
+        DynamicImage::ImageLuma16(image::ImageBuffer::from_pixel(8, 4, image::Luma([0]))),
+    ];
+
+    for image in images {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("{:?}.jpg", image.color()));
+
+        Encoder::new(std::fs::File::create(&path).unwrap(), image)
+            .with_config(EncoderConfig::new(Codec::MozJpeg))
+            .encode()
+            .unwrap();
+
+        let decoded = image::open(&path).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height()), (8, 4));
+    }
+}
+
+#[test]
+fn encode_legacy_codecs() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(16, 8, |x, y| {
+        image::Rgba([(x * 16) as u8, (y * 32) as u8, 128, 255])
+    }));
+
+    for codec in [Codec::Tiff, Codec::Gif, Codec::Qoi, Codec::Bmp] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("image.{}", codec.to_extension()));
+
+        Encoder::new(std::fs::File::create(&path).unwrap(), image.clone())
+            .with_config(EncoderConfig::new(codec))
+            .encode()
+            .unwrap();
+
+        let decoded = image::open(&path).unwrap();
+
+        assert_eq!(decoded.width(), 16, "{codec:?}");
+        assert_eq!(decoded.height(), 8, "{codec:?}");
+
+        // GIF is quantized, other codecs are lossless
+        if codec != Codec::Gif {
+            assert_eq!(decoded.to_rgba8(), image.to_rgba8(), "{codec:?}");
+        }
+    }
+}
+
+#[test]
+fn encode_to_non_seekable_writer() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(16, 8, |x, y| {
+        image::Rgba([(x * 16) as u8, (y * 32) as u8, 128, 255])
+    }));
+
+    let codecs = [
+        Codec::MozJpeg,
+        Codec::Png,
+        #[cfg(feature = "oxipng")]
+        Codec::OxiPng,
+        Codec::Tiff,
+        Codec::Gif,
+        Codec::Qoi,
+        Codec::Bmp,
+    ];
+
+    for codec in codecs {
+        let mut buf = vec![];
+
+        Encoder::new(&mut buf, image.clone())
+            .with_config(EncoderConfig::new(codec))
+            .encode()
+            .unwrap();
+
+        let decoded = image::load_from_memory(&buf).unwrap();
+
+        assert_eq!(decoded.width(), 16, "{codec:?}");
+        assert_eq!(decoded.height(), 8, "{codec:?}");
+    }
+}
+
+#[test]
+fn encode_tiff_preserves_16_bit() {
+    use crate::config::TiffCompression;
+
+    let image = DynamicImage::ImageRgb16(image::ImageBuffer::from_fn(8, 4, |x, y| {
+        image::Rgb([x as u16 * 8000, y as u16 * 16000, 1])
+    }));
+
+    for compression in [
+        TiffCompression::None,
+        TiffCompression::Lzw,
+        TiffCompression::Deflate,
+    ] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(format!("16_bit_{compression:?}.tiff"));
+
+        Encoder::new(std::fs::File::create(&path).unwrap(), image.clone())
+            .with_config(EncoderConfig::new(Codec::Tiff).with_tiff_compression(compression))
+            .encode()
+            .unwrap();
+
+        let decoded = image::open(&path).unwrap();
+
+        assert_eq!(decoded.color(), ColorType::Rgb16);
+        assert_eq!(decoded, image);
+    }
+}
+
+#[test]
+#[cfg(feature = "quantization")]
+fn encode_gif_with_transparency() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("transparent.gif");
+
+    let image = RgbaImage::from_fn(8, 8, |x, _| {
+        if x < 4 {
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            image::Rgba([0, 0, 0, 0])
+        }
+    });
+
+    Encoder::new(
+        std::fs::File::create(&path).unwrap(),
+        DynamicImage::ImageRgba8(image),
+    )
+    .with_config(EncoderConfig::new(Codec::Gif))
+    .encode()
+    .unwrap();
+
+    let decoded = image::open(&path).unwrap().into_rgba8();
+
+    assert_eq!(decoded.get_pixel(0, 0), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(decoded.get_pixel(7, 7)[3], 0);
+}
+
+#[test]
+fn report_encoding_stages() {
+    use std::sync::{Arc, Mutex};
+
+    let encode = |image: DynamicImage, config: EncoderConfig| {
+        let stages = Arc::new(Mutex::new(vec![]));
+        let reported = stages.clone();
+
+        Encoder::new(vec![], image)
+            .with_config(config)
+            .with_progress(move |stage| reported.lock().unwrap().push(stage))
+            .encode()
+            .unwrap();
+
+        Arc::try_unwrap(stages).unwrap().into_inner().unwrap()
+    };
+
+    let image = DynamicImage::ImageRgba8(RgbaImage::new(8, 8));
+
+    assert_eq!(
+        encode(image.clone(), EncoderConfig::new(Codec::Png)),
+        [EncodingStage::Compressing, EncodingStage::Finished]
+    );
+    assert_eq!(
+        encode(image.clone(), EncoderConfig::new(Codec::MozJpeg)),
+        [
+            EncodingStage::Flattening,
+            EncodingStage::Compressing,
+            EncodingStage::Finished
+        ]
+    );
+
+    #[cfg(all(feature = "quantization", feature = "resizing"))]
+    {
+        use crate::config::{QuantizationConfig, ResizeConfig, ResizeType};
+
+        let config = EncoderConfig::new(Codec::Qoi)
+            .with_quantization(QuantizationConfig::new())
+            .with_resize(ResizeConfig::new(ResizeType::Lanczos3).with_width(4));
+
+        assert_eq!(
+            encode(image, config),
+            [
+                EncodingStage::Resizing,
+                EncodingStage::Quantizing,
+                EncodingStage::Compressing,
+                EncodingStage::Finished
+            ]
+        );
+    }
+}
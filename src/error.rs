@@ -16,6 +16,30 @@ pub enum InvalidQuantizationConfig {
     /// Error indicating that the dithering level is out of bounds.
     #[error("Dithering level {0} is out of bounds (0.0-1.0).")]
     DitheringOutOfBounds(f32),
+
+    /// Error indicating that the minimum quality is greater than the maximum quality.
+    #[error("Minimum quality {0} is greater than maximum quality {1}.")]
+    InvalidQualityRange(u8, u8),
+
+    /// Error indicating that the number of colors is out of bounds.
+    #[error("Max colors value {0} is out of bounds (2-256).")]
+    MaxColorsOutOfBounds(u32),
+
+    /// Error indicating that the speed value is out of bounds.
+    #[error("Speed value {0} is out of bounds (1-10).")]
+    SpeedOutOfBounds(u8),
+
+    /// Error indicating that the posterization bits value is out of bounds.
+    #[error("Posterization value {0} is out of bounds (0-4).")]
+    PosterizationOutOfBounds(u8),
+
+    /// Error indicating that the gamma value is out of bounds.
+    #[error("Gamma value {0} is out of bounds (0.0-1.0).")]
+    GammaOutOfBounds(f64),
+
+    /// Error indicating that the number of palette colors is out of bounds.
+    #[error("Palette size {0} is out of bounds (2-256).")]
+    PaletteSizeOutOfBounds(usize),
 }
 
 /// Error type for invalid encoder configuration.
@@ -28,6 +52,20 @@ pub enum InvalidEncoderConfig {
     QualityOutOfBounds(f32),
 }
 
+/// Error type for invalid tone mapping configuration.
+///
+/// This error is returned when the input values for [`ToneMappingConfig`] are out of the valid range.
+#[derive(Error, Debug)]
+pub enum InvalidToneMappingConfig {
+    /// Error indicating that the exposure is out of bounds.
+    #[error("Exposure {0} is out of bounds (-20.0-20.0).")]
+    ExposureOutOfBounds(f32),
+
+    /// Error indicating that the peak luminance is out of bounds.
+    #[error("Peak luminance {0} is out of bounds (203.0-10000.0).")]
+    PeakLuminanceOutOfBounds(f32),
+}
+
 /// Enum representing errors related to parsing image formats.
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ImageFormatError {
@@ -115,6 +153,80 @@ pub enum DecoderError {
     WebP,
 }
 
+/// Checks if encoding failed because quantization couldn't reach the minimum quality.
+///
+/// [`Encoder::encode`](crate::Encoder::encode) returns such an error when a minimum quality
+/// is set with [`QuantizationConfig::with_quality_range`](crate::config::QuantizationConfig::with_quality_range)
+/// and the image needs more colors than allowed to reach it. Callers usually skip such images
+/// and keep the original file.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::{error::is_quality_too_low, Encoder, config::{Codec, EncoderConfig, QuantizationConfig}};
+/// use image::{DynamicImage, RgbImage};
+///
+/// let image = RgbImage::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 0]));
+///
+/// let quantization = QuantizationConfig::new()
+///     .with_quality_range(100, 100).unwrap()
+///     .with_max_colors(2).unwrap();
+///
+/// let config = EncoderConfig::new(Codec::Png).with_quantization(quantization);
+///
+/// let result = Encoder::new(std::io::Cursor::new(Vec::new()), DynamicImage::ImageRgb8(image))
+///     .with_config(config)
+///     .encode();
+///
+/// assert!(is_quality_too_low(&result.unwrap_err()));
+/// ```
+#[cfg(feature = "quantization")]
+pub fn is_quality_too_low(error: &image::ImageError) -> bool {
+    use std::error::Error;
+
+    matches!(error, image::ImageError::Encoding(e)
+        if e.source().and_then(|e| e.downcast_ref::<imagequant::Error>())
+            == Some(&imagequant::Error::QualityTooLow))
+}
+
+/// Enum representing errors that can occur during image operations.
+#[derive(Error, Debug, PartialEq)]
+pub enum OperationError {
+    /// Error indicating that the crop area is empty or doesn't fit inside of the image.
+    #[error("Crop area {width}x{height} at ({x}, {y}) is out of image bounds.")]
+    CropOutOfBounds {
+        /// Left edge of the crop area.
+        x: usize,
+        /// Top edge of the crop area.
+        y: usize,
+        /// Width of the crop area.
+        width: usize,
+        /// Height of the crop area.
+        height: usize,
+    },
+
+    /// Error indicating that the gamma value is not a positive number.
+    #[error("Gamma value {0} must be a positive number.")]
+    InvalidGamma(f32),
+
+    /// Error indicating that the rotation angle is not a finite number.
+    #[error("Rotation angle {0} must be a finite number.")]
+    InvalidAngle(f32),
+
+    /// Error indicating that the padded image would be too large to store.
+    #[error("Padding {top},{right},{bottom},{left} makes the image too large.")]
+    PaddingTooLarge {
+        /// Size of the top border.
+        top: usize,
+        /// Size of the right border.
+        right: usize,
+        /// Size of the bottom border.
+        bottom: usize,
+        /// Size of the left border.
+        left: usize,
+    },
+}
+
 /// An error occurred when encoding JpegXL image
 #[cfg(feature = "jxl")]
 #[derive(Error, Debug)]
@@ -141,6 +253,20 @@ mod tests {
             format!("{}", dithering_error),
             "Dithering level 1.5 is out of bounds (0.0-1.0)."
         );
+
+        // Test InvalidQualityRange error
+        let range_error = InvalidQuantizationConfig::InvalidQualityRange(80, 60);
+        assert_eq!(
+            format!("{}", range_error),
+            "Minimum quality 80 is greater than maximum quality 60."
+        );
+
+        // Test MaxColorsOutOfBounds error
+        let colors_error = InvalidQuantizationConfig::MaxColorsOutOfBounds(300);
+        assert_eq!(
+            format!("{}", colors_error),
+            "Max colors value 300 is out of bounds (2-256)."
+        );
     }
 
     #[test]
@@ -154,6 +280,24 @@ mod tests {
     }
 
     #[test]
+    fn invalid_tone_mapping_config_errors() {
+        // Test ExposureOutOfBounds error
+        let exposure_error = InvalidToneMappingConfig::ExposureOutOfBounds(-25.0);
+        assert_eq!(
+            format!("{}", exposure_error),
+            "Exposure -25 is out of bounds (-20.0-20.0)."
+        );
+
+        // Test PeakLuminanceOutOfBounds error
+        let peak_error = InvalidToneMappingConfig::PeakLuminanceOutOfBounds(100.0);
+        assert_eq!(
+            format!("{}", peak_error),
+            "Peak luminance 100 is out of bounds (203.0-10000.0)."
+        );
+    }
+
+    #[test]
+    #[allow(clippy::io_other_error)]
     fn encoder_error_messages() {
         // Test Io error message
         let io_error = EncoderError::Io(std::io::Error::new(std::io::ErrorKind::Other, "IO error"));
@@ -179,4 +323,41 @@ mod tests {
 
         // no need to test others 🤷‍♀️
     }
+
+    #[test]
+    fn operation_errors() {
+        let crop_error = OperationError::CropOutOfBounds {
+            x: 10,
+            y: 20,
+            width: 30,
+            height: 40,
+        };
+        assert_eq!(
+            format!("{}", crop_error),
+            "Crop area 30x40 at (10, 20) is out of image bounds."
+        );
+
+        let gamma_error = OperationError::InvalidGamma(-1.0);
+        assert_eq!(
+            format!("{}", gamma_error),
+            "Gamma value -1 must be a positive number."
+        );
+
+        let angle_error = OperationError::InvalidAngle(f32::INFINITY);
+        assert_eq!(
+            format!("{}", angle_error),
+            "Rotation angle inf must be a finite number."
+        );
+
+        let padding_error = OperationError::PaddingTooLarge {
+            top: 1,
+            right: usize::MAX,
+            bottom: 1,
+            left: 0,
+        };
+        assert_eq!(
+            format!("{}", padding_error),
+            format!("Padding 1,{},1,0 makes the image too large.", usize::MAX)
+        );
+    }
 }
@@ -5,11 +5,13 @@ Rimage is a powerful Rust image optimization library designed to simplify and en
 
 ## Features
 
-1. **Flexible Format Conversion**: Rimage supports all modern image formats, including JPEG, JPEG XL, PNG, AVIF, and WebP.
+1. **Flexible Format Conversion**: Rimage supports all modern image formats, including JPEG, JPEG XL, PNG, AVIF, and WebP. Legacy formats like TIFF, GIF, BMP, TGA, ICO, QOI, PNM, DDS, Radiance HDR and OpenEXR can be decoded as well. HEIF photos are decoded, SVG images are rasterized at the target size and camera raw images are developed into sRGB.
 2. **Quality Control**: Fine-tune the quality of your images using a simple and intuitive interface.
 3. **Parallel Optimization**: Harness the power of parallel processing to optimize multiple images simultaneously.
 4. **Quantization and Dithering**: For advanced users, Rimage offers control over quantization and dithering.
 5. **Image Resizing**: Resize images with ease using `resize` crate.
+6. **Image Operations**: Crop, pad, rotate, flip, trim and adjust colors of images before encoding.
+7. **Async API**: With `async` feature, `AsyncDecoder` and `AsyncEncoder` read and write tokio streams and run the heavy work on a `BlockingPool`.
 
 ## Decoding
 
@@ -65,20 +67,46 @@ encoder.encode()?;
 # std::fs::remove_file("output.jpg").unwrap_or(());
 # Ok::<(), image::ImageError>(())
 ```
+
+## Operations
+
+```
+use rimage::{Decoder, Encoder, Image, Operation};
+use image::DynamicImage;
+
+let decoder = Decoder::from_path("tests/files/png/f1t.png")?;
+
+let mut image = Image::from(decoder.decode()?);
+
+image.apply(&Operation::Crop { x: 0, y: 0, width: 48, height: 48 })?;
+image.apply(&Operation::Rotate(90.0))?;
+image.apply(&Operation::Grayscale)?;
+
+let output = std::io::Cursor::new(Vec::new());
+
+Encoder::new(output, DynamicImage::from(image)).encode()?;
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
 */
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "async")]
+mod asynchronous;
 ///  Module for configuring image processing settings.
 pub mod config;
 mod decoder;
 mod encoder;
 ///  Module for library errors.
 pub mod error;
+mod operations;
 
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncDecoder, AsyncEncoder, BlockingPool};
 pub use decoder::Decoder;
-pub use encoder::Encoder;
+pub use encoder::{Encoder, EncodingStage};
 pub use image;
+pub use operations::{Image, Operation};
 
 #[cfg(feature = "resizing")]
 pub use resize;
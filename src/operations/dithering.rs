@@ -0,0 +1,375 @@
+//! Palette remapping with dithering methods that `imagequant` doesn't provide.
+
+use std::sync::OnceLock;
+
+use rgb::RGBA8;
+
+use crate::config::Dithering;
+
+/// Size of the blue noise threshold map.
+const BLUE_NOISE_SIZE: usize = 64;
+
+/// Remaps pixels onto the palette, returning per-pixel palette indices.
+///
+/// `level` scales the strength of dithering, 0.0 results in plain nearest color mapping.
+pub(crate) fn remap(
+    pixels: &[RGBA8],
+    width: usize,
+    palette: &[RGBA8],
+    method: Dithering,
+    level: f32,
+) -> Vec<u8> {
+    match method {
+        Dithering::FloydSteinberg => diffuse_error(
+            pixels,
+            width,
+            palette,
+            &[
+                (1, 0, 7.0 / 16.0),
+                (-1, 1, 3.0 / 16.0),
+                (0, 1, 5.0 / 16.0),
+                (1, 1, 1.0 / 16.0),
+            ],
+            level,
+        ),
+        Dithering::Atkinson => diffuse_error(
+            pixels,
+            width,
+            palette,
+            &[
+                (1, 0, 1.0 / 8.0),
+                (2, 0, 1.0 / 8.0),
+                (-1, 1, 1.0 / 8.0),
+                (0, 1, 1.0 / 8.0),
+                (1, 1, 1.0 / 8.0),
+                (0, 2, 1.0 / 8.0),
+            ],
+            level,
+        ),
+        Dithering::Ordered => threshold(pixels, width, palette, level, |x, y| {
+            (bayer(x % 8, y % 8, 3) as f32 + 0.5) / 64.0 - 0.5
+        }),
+        Dithering::BlueNoise => {
+            let map = blue_noise();
+
+            threshold(pixels, width, palette, level, |x, y| {
+                let rank = map[(y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE];
+                (rank as f32 + 0.5) / (BLUE_NOISE_SIZE * BLUE_NOISE_SIZE) as f32 - 0.5
+            })
+        }
+    }
+}
+
+/// Finds the index of the palette color closest to the specified color.
+fn nearest(palette: &[RGBA8], color: [f32; 4]) -> u8 {
+    palette
+        .iter()
+        .map(|c| {
+            [c.r, c.g, c.b, c.a]
+                .iter()
+                .zip(color)
+                .map(|(a, b)| (*a as f32 - b).powi(2))
+                .sum::<f32>()
+        })
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i as u8)
+        .unwrap_or_default()
+}
+
+/// Error diffusion dithering with the specified `(dx, dy, weight)` kernel.
+fn diffuse_error(
+    pixels: &[RGBA8],
+    width: usize,
+    palette: &[RGBA8],
+    kernel: &[(isize, usize, f32)],
+    level: f32,
+) -> Vec<u8> {
+    let height = pixels.len() / width.max(1);
+
+    let mut buffer: Vec<[f32; 4]> = pixels
+        .iter()
+        .map(|p| [p.r as f32, p.g as f32, p.b as f32, p.a as f32])
+        .collect();
+
+    let mut indices = Vec::with_capacity(pixels.len());
+
+    for y in 0..height {
+        for x in 0..width {
+            let color = buffer[y * width + x].map(|c| c.clamp(0.0, 255.0));
+
+            let index = nearest(palette, color);
+            indices.push(index);
+
+            let c = palette[index as usize];
+            let error = [
+                color[0] - c.r as f32,
+                color[1] - c.g as f32,
+                color[2] - c.b as f32,
+                color[3] - c.a as f32,
+            ];
+
+            for &(dx, dy, weight) in kernel {
+                let (nx, ny) = (x as isize + dx, y + dy);
+
+                if nx < 0 || nx >= width as isize || ny >= height {
+                    continue;
+                }
+
+                let target = &mut buffer[ny * width + nx as usize];
+
+                for (t, e) in target.iter_mut().zip(error) {
+                    *t += e * weight * level;
+                }
+            }
+        }
+    }
+
+    indices
+}
+
+/// Ordered dithering with a threshold map returning values in range -0.5..0.5.
+fn threshold(
+    pixels: &[RGBA8],
+    width: usize,
+    palette: &[RGBA8],
+    level: f32,
+    map: impl Fn(usize, usize) -> f32,
+) -> Vec<u8> {
+    let spread = spread(palette) * level;
+
+    pixels
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let offset = map(i % width, i / width) * spread;
+
+            nearest(
+                palette,
+                [
+                    (p.r as f32 + offset).clamp(0.0, 255.0),
+                    (p.g as f32 + offset).clamp(0.0, 255.0),
+                    (p.b as f32 + offset).clamp(0.0, 255.0),
+                    p.a as f32,
+                ],
+            )
+        })
+        .collect()
+}
+
+/// Estimates the distance between neighbouring palette colors, which is the amplitude
+/// ordered dithering needs to mix them.
+fn spread(palette: &[RGBA8]) -> f32 {
+    if palette.len() < 2 {
+        return 0.0;
+    }
+
+    let total: u32 = palette
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            palette
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, b)| {
+                    a.r.abs_diff(b.r)
+                        .max(a.g.abs_diff(b.g))
+                        .max(a.b.abs_diff(b.b)) as u32
+                })
+                .min()
+                .unwrap_or_default()
+        })
+        .sum();
+
+    total as f32 / palette.len() as f32
+}
+
+/// Gets the value of a `2^n` x `2^n` Bayer matrix.
+fn bayer(x: usize, y: usize, n: u32) -> usize {
+    (0..n).fold(0, |value, bit| {
+        let xor = ((x ^ y) >> bit) & 1;
+        let row = (y >> bit) & 1;
+
+        value | ((xor << 1 | row) << (2 * (n - 1 - bit)))
+    })
+}
+
+/// Gets the blue noise threshold map with a rank of every pixel.
+fn blue_noise() -> &'static [u16] {
+    static MAP: OnceLock<Vec<u16>> = OnceLock::new();
+
+    MAP.get_or_init(|| void_and_cluster(BLUE_NOISE_SIZE))
+}
+
+/// Generates a blue noise threshold map using the void-and-cluster method.
+fn void_and_cluster(size: usize) -> Vec<u16> {
+    let len = size * size;
+
+    // Gaussian energy of a pixel at toroidal offset, sigma = 1.5
+    let kernel: Vec<f32> = (0..len)
+        .map(|i| {
+            let (dx, dy) = (i % size, i / size);
+            let (dx, dy) = (dx.min(size - dx) as f32, dy.min(size - dy) as f32);
+
+            (-(dx * dx + dy * dy) / (2.0 * 1.5 * 1.5)).exp()
+        })
+        .collect();
+
+    let mut pattern = vec![false; len];
+    let mut energy = vec![0.0f32; len];
+
+    let toggle = |pattern: &mut [bool], energy: &mut [f32], i: usize| {
+        pattern[i] = !pattern[i];
+        let sign = if pattern[i] { 1.0 } else { -1.0 };
+        let (x, y) = (i % size, i / size);
+
+        for (j, e) in energy.iter_mut().enumerate() {
+            let (dx, dy) = ((j % size + size - x) % size, (j / size + size - y) % size);
+            *e += sign * kernel[dy * size + dx];
+        }
+    };
+
+    let tightest_cluster = |pattern: &[bool], energy: &[f32]| {
+        (0..len)
+            .filter(|i| pattern[*i])
+            .max_by(|a, b| energy[*a].total_cmp(&energy[*b]))
+            .unwrap()
+    };
+
+    let largest_void = |pattern: &[bool], energy: &[f32]| {
+        (0..len)
+            .filter(|i| !pattern[*i])
+            .min_by(|a, b| energy[*a].total_cmp(&energy[*b]))
+            .unwrap()
+    };
+
+    // Initial pattern from a deterministic pseudo-random sequence
+    let mut seed = 0x2545_f491_u32;
+    let mut ones = 0;
+
+    while ones < len / 10 {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+
+        let i = seed as usize % len;
+
+        if !pattern[i] {
+            toggle(&mut pattern, &mut energy, i);
+            ones += 1;
+        }
+    }
+
+    // Move points from tightest clusters to largest voids until the pattern is uniform
+    loop {
+        let cluster = tightest_cluster(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, cluster);
+
+        let void = largest_void(&pattern, &energy);
+
+        if void == cluster {
+            toggle(&mut pattern, &mut energy, cluster);
+            break;
+        }
+
+        toggle(&mut pattern, &mut energy, void);
+    }
+
+    let mut ranks = vec![0u16; len];
+
+    // Rank points of the initial pattern by removing tightest clusters
+    let (mut prototype, mut prototype_energy) = (pattern.clone(), energy.clone());
+
+    for rank in (0..ones).rev() {
+        let cluster = tightest_cluster(&prototype, &prototype_energy);
+        toggle(&mut prototype, &mut prototype_energy, cluster);
+        ranks[cluster] = rank as u16;
+    }
+
+    // Rank remaining points by filling largest voids
+    for rank in ones..len {
+        let void = largest_void(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, void);
+        ranks[void] = rank as u16;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: RGBA8 = RGBA8::new(0, 0, 0, 255);
+    const WHITE: RGBA8 = RGBA8::new(255, 255, 255, 255);
+
+    fn white_ratio(indices: &[u8]) -> f32 {
+        indices.iter().filter(|i| **i == 1).count() as f32 / indices.len() as f32
+    }
+
+    #[test]
+    fn bayer_matrix() {
+        let matrix: Vec<usize> = (0..4).map(|i| bayer(i % 2, i / 2, 1)).collect();
+        assert_eq!(matrix, vec![0, 2, 3, 1]);
+
+        let mut values: Vec<usize> = (0..64).map(|i| bayer(i % 8, i / 8, 3)).collect();
+        values.sort();
+        assert_eq!(values, (0..64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn blue_noise_ranks() {
+        let mut ranks = blue_noise().to_vec();
+        ranks.sort();
+
+        assert_eq!(ranks, (0..64 * 64).collect::<Vec<u16>>());
+    }
+
+    #[test]
+    fn palette_spread() {
+        assert_eq!(spread(&[BLACK, WHITE]), 255.0);
+
+        let grays: Vec<RGBA8> = [0, 85, 170, 255]
+            .iter()
+            .map(|v| RGBA8::new(*v, *v, *v, 255))
+            .collect();
+        assert_eq!(spread(&grays), 85.0);
+    }
+
+    #[test]
+    fn dithering_preserves_average() {
+        let gray = vec![RGBA8::new(64, 64, 64, 255); 32 * 32];
+
+        for method in [
+            Dithering::FloydSteinberg,
+            Dithering::Ordered,
+            Dithering::BlueNoise,
+        ] {
+            let indices = remap(&gray, 32, &[BLACK, WHITE], method, 1.0);
+            let ratio = white_ratio(&indices);
+
+            assert!(
+                (ratio - 0.25).abs() < 0.05,
+                "{method:?} produced {ratio} white pixels"
+            );
+        }
+
+        // Atkinson drops a quarter of the error, which pushes dark tones further to black
+        let ratio = white_ratio(&remap(&gray, 32, &[BLACK, WHITE], Dithering::Atkinson, 1.0));
+        assert!(
+            ratio > 0.1 && ratio < 0.25,
+            "Atkinson produced {ratio} white pixels"
+        );
+    }
+
+    #[test]
+    fn no_dithering() {
+        let gray = vec![RGBA8::new(100, 100, 100, 255); 16];
+
+        for method in [Dithering::Atkinson, Dithering::Ordered] {
+            let indices = remap(&gray, 4, &[BLACK, WHITE], method, 0.0);
+            assert_eq!(white_ratio(&indices), 0.0);
+        }
+    }
+}
@@ -0,0 +1,1080 @@
+use image::{DynamicImage, RgbaImage};
+use rgb::{FromSlice, RGBA8};
+
+use crate::config::Background;
+#[cfg(feature = "resizing")]
+use crate::config::ResizeConfig;
+#[cfg(feature = "quantization")]
+use crate::config::{Palette, QuantizationConfig};
+use crate::error::OperationError;
+
+/// Enum representing a single image operation.
+///
+/// Operations can be collected into a list and applied one after another
+/// to an [`Image`] with [`Image::apply`], which makes it easy to describe a
+/// processing pipeline that runs between decoding and encoding.
+///
+/// # Examples
+///
+/// ```
+/// use rimage::{Image, Operation};
+/// # use rimage::rgb::RGBA8;
+///
+/// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 255); 100 * 50], 100, 50);
+///
+/// let operations = [
+///     Operation::Crop { x: 10, y: 10, width: 50, height: 25 },
+///     Operation::Rotate(90.0),
+///     Operation::Grayscale,
+/// ];
+///
+/// for operation in &operations {
+///     image.apply(operation)?;
+/// }
+///
+/// assert_eq!(image.width(), 25);
+/// assert_eq!(image.height(), 50);
+/// # Ok::<(), rimage::error::OperationError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    /// Crops the image to the given rectangle, see [`Image::crop`].
+    Crop {
+        /// Left edge of the crop area.
+        x: usize,
+        /// Top edge of the crop area.
+        y: usize,
+        /// Width of the crop area.
+        width: usize,
+        /// Height of the crop area.
+        height: usize,
+    },
+    /// Adds borders filled with a color around the image, see [`Image::pad`].
+    Pad {
+        /// Size of the top border.
+        top: usize,
+        /// Size of the right border.
+        right: usize,
+        /// Size of the bottom border.
+        bottom: usize,
+        /// Size of the left border.
+        left: usize,
+        /// Color of the added borders.
+        color: RGBA8,
+    },
+    /// Rotates the image clockwise by the given angle in degrees, see [`Image::rotate`].
+    ///
+    /// Uncovered areas are filled with transparent pixels.
+    Rotate(f32),
+    /// Flips the image horizontally, see [`Image::flip_horizontally`].
+    FlipHorizontally,
+    /// Flips the image vertically, see [`Image::flip_vertically`].
+    FlipVertically,
+    /// Trims uniform borders with the given tolerance, see [`Image::trim`].
+    Trim(u8),
+    /// Converts the image to grayscale, see [`Image::grayscale`].
+    Grayscale,
+    /// Adjusts the image brightness, see [`Image::brightness`].
+    Brightness(i16),
+    /// Adjusts the image contrast, see [`Image::contrast`].
+    Contrast(f32),
+    /// Applies gamma correction, see [`Image::gamma`].
+    Gamma(f32),
+}
+
+/// Struct representing an image with RGBA8 pixel data.
+///
+/// [`Image`] can be created from the [`DynamicImage`] returned by
+/// [`Decoder::decode`](crate::Decoder::decode) and converted back into one
+/// before it is passed to [`Encoder::new`](crate::Encoder::new).
+///
+/// Pixels are always stored as RGBA8, so 16-bit and floating point images lose their extra
+/// precision on conversion and HDR images are clipped instead of tone mapped by the
+/// [`Encoder`](crate::Encoder).
+///
+/// # Examples
+///
+/// ```
+/// use rimage::{Decoder, Image};
+/// use image::DynamicImage;
+///
+/// let decoder = Decoder::from_path("tests/files/png/f1t.png")?;
+///
+/// let mut image = Image::from(decoder.decode()?);
+///
+/// image.rotate_90();
+/// image.flip_horizontally();
+///
+/// let image = DynamicImage::from(image);
+///
+/// assert_eq!(image.width(), 80);
+/// assert_eq!(image.height(), 48);
+/// # Ok::<(), image::ImageError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Image {
+    data: Vec<RGBA8>,
+    width: usize,
+    height: usize,
+}
+
+impl Image {
+    /// Creates a new [`Image`] instance with the given pixel data, width, and height.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: A vector containing RGBA8 pixel data.
+    /// - `width`: The width of the image in pixels.
+    /// - `height`: The height of the image in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rimage::Image;
+    /// use rgb::RGBA8;
+    ///
+    /// let pixel_data: Vec<RGBA8> = vec![/* pixel data */];
+    /// let image = Image::new(pixel_data, 800, 600);
+    /// ```
+    #[inline]
+    pub fn new(data: Vec<RGBA8>, width: usize, height: usize) -> Self {
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Applies a single [`Operation`] to the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `operation`: The operation to apply.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success or an [`OperationError`] if the operation parameters are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::{Image, Operation};
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 255); 100 * 50], 100, 50);
+    ///
+    /// image.apply(&Operation::Rotate(270.0))?;
+    ///
+    /// assert_eq!(image.width(), 50);
+    /// assert_eq!(image.height(), 100);
+    /// # Ok::<(), rimage::error::OperationError>(())
+    /// ```
+    pub fn apply(&mut self, operation: &Operation) -> Result<(), OperationError> {
+        match *operation {
+            Operation::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => self.crop(x, y, width, height)?,
+            Operation::Pad {
+                top,
+                right,
+                bottom,
+                left,
+                color,
+            } => self.pad(top, right, bottom, left, color)?,
+            Operation::Rotate(degrees) if !degrees.is_finite() => {
+                return Err(OperationError::InvalidAngle(degrees))
+            }
+            Operation::Rotate(degrees) => self.rotate(degrees, RGBA8::new(0, 0, 0, 0)),
+            Operation::FlipHorizontally => self.flip_horizontally(),
+            Operation::FlipVertically => self.flip_vertically(),
+            Operation::Trim(tolerance) => self.trim(tolerance),
+            Operation::Grayscale => self.grayscale(),
+            Operation::Brightness(value) => self.brightness(value),
+            Operation::Contrast(value) => self.contrast(value),
+            Operation::Gamma(value) => self.gamma(value)?,
+        }
+
+        Ok(())
+    }
+
+    /// Resizes the image using the specified [`ResizeConfig`].
+    ///
+    /// # Parameters
+    ///
+    /// - `resize_config`: The configuration for resizing the image.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success or a [`resize::Error`] on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::{Image, config::ResizeConfig};
+    ///
+    /// let image_data = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    /// let mut image = Image::new(image_data, 800, 600);
+    ///
+    /// let resize_config = ResizeConfig::default()
+    ///     .with_width(400);
+    ///
+    /// image.resize(&resize_config)?;
+    /// # Ok::<(), rimage::resize::Error>(())
+    /// ```
+    #[cfg(feature = "resizing")]
+    pub fn resize(&mut self, resize_config: &ResizeConfig) -> Result<(), resize::Error> {
+        let aspect_ratio = self.width as f64 / self.height as f64;
+
+        let width = resize_config.width().unwrap_or(
+            resize_config
+                .height()
+                .map(|h| (h as f64 * aspect_ratio) as usize)
+                .unwrap_or(self.width),
+        );
+        let height = resize_config.height().unwrap_or(
+            resize_config
+                .width()
+                .map(|w| (w as f64 / aspect_ratio) as usize)
+                .unwrap_or(self.height),
+        );
+
+        let mut buf: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); width * height];
+
+        let mut resizer = resize::new(
+            self.width,
+            self.height,
+            width,
+            height,
+            resize::Pixel::RGBA8,
+            resize_config.filter_type().into(),
+        )?;
+
+        resizer.resize(&self.data, &mut buf)?;
+
+        self.data = buf;
+        self.width = width;
+        self.height = height;
+
+        Ok(())
+    }
+
+    /// Quantizes the image using the specified [`QuantizationConfig`].
+    ///
+    /// # Parameters
+    ///
+    /// - `quantization_config`: The configuration for quantizing the image.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success or an [`imagequant::Error`] on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::{Image, config::QuantizationConfig};
+    ///
+    /// let image_data = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    /// let mut image = Image::new(image_data, 800, 600);
+    ///
+    /// let quantization_config = QuantizationConfig::default();
+    /// image.quantize(&quantization_config).unwrap();
+    /// ```
+    #[cfg(feature = "quantization")]
+    pub fn quantize(
+        &mut self,
+        quantization_config: &QuantizationConfig,
+    ) -> Result<(), imagequant::Error> {
+        let (palette, pixels) =
+            crate::config::quantize(&self.data, self.width, self.height, quantization_config)?;
+
+        self.data = pixels.iter().map(|pix| palette[*pix as usize]).collect();
+
+        Ok(())
+    }
+
+    /// Remaps the image onto a fixed palette.
+    ///
+    /// # Parameters
+    ///
+    /// - `palette`: The palette used for remapping.
+    /// - `quantization_config`: The configuration used for dithering.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success or an [`imagequant::Error`] on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::{Image, config::{Palette, QuantizationConfig}};
+    ///
+    /// let black = RGBA8::new(0, 0, 0, 255);
+    /// let white = RGBA8::new(255, 255, 255, 255);
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(20, 20, 20, 255); 4], 2, 2);
+    ///
+    /// let palette = Palette::new(vec![black, white]).unwrap();
+    /// image.remap(&palette, &QuantizationConfig::default()).unwrap();
+    ///
+    /// assert_eq!(image.data(), &[black; 4]);
+    /// ```
+    #[cfg(feature = "quantization")]
+    pub fn remap(
+        &mut self,
+        palette: &Palette,
+        quantization_config: &QuantizationConfig,
+    ) -> Result<(), imagequant::Error> {
+        let (palette, pixels) =
+            palette.remap(&self.data, self.width, self.height, quantization_config)?;
+
+        self.data = pixels.iter().map(|pix| palette[*pix as usize]).collect();
+
+        Ok(())
+    }
+
+    /// Fixes the orientation of the image based on the given orientation value.
+    ///
+    /// This method applies various transformations to correct the orientation of the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `orientation`: An integer value representing the image orientation. It should be a value
+    ///   between 1 and 8, inclusive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.fix_orientation(3); // Fix the orientation of the image
+    /// ```
+    #[cfg(feature = "transform")]
+    pub fn fix_orientation(&mut self, orientation: u32) {
+        if !(1..=8).contains(&orientation) {
+            return;
+        }
+
+        let orientation = orientation - 1;
+
+        if orientation & 0b100 != 0 {
+            self.flip_diagonally();
+        }
+
+        if orientation & 0b010 != 0 {
+            self.rotate_180();
+        }
+
+        if orientation & 0b001 != 0 {
+            self.flip_horizontally();
+        }
+    }
+
+    /// Flips the image diagonally.
+    ///
+    /// This method performs a diagonal flip (transpose) of the image data and updates the image dimensions accordingly.
+    #[inline]
+    fn flip_diagonally(&mut self) {
+        let mut buf = Vec::with_capacity(self.data.len());
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                buf.push(self.data[y * self.width + x]);
+            }
+        }
+
+        self.data = buf;
+        (self.width, self.height) = (self.height, self.width);
+    }
+
+    /// Flips the image horizontally.
+    ///
+    /// This method performs a horizontal flip of the image data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.flip_horizontally(); // Flip the image horizontally
+    /// ```
+    #[inline]
+    pub fn flip_horizontally(&mut self) {
+        for y in 0..self.height {
+            let start = y * self.width;
+
+            self.data[start..start + self.width].reverse();
+        }
+    }
+
+    /// Flips the image vertically.
+    ///
+    /// This method swaps the order of the image rows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.flip_vertically(); // Flip the image vertically
+    /// ```
+    #[inline]
+    pub fn flip_vertically(&mut self) {
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.data.split_at_mut((self.height - y - 1) * self.width);
+
+            top[y * self.width..(y + 1) * self.width].swap_with_slice(&mut bottom[..self.width]);
+        }
+    }
+
+    /// Rotates the image 90 degrees clockwise.
+    ///
+    /// This method rotates the image 90 degrees clockwise by performing a diagonal flip followed by a horizontal flip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.rotate_90(); // Rotate the image 90 degrees clockwise
+    ///
+    /// assert_eq!(image.width(), 600);
+    /// ```
+    #[inline]
+    pub fn rotate_90(&mut self) {
+        self.flip_diagonally();
+        self.flip_horizontally();
+    }
+
+    /// Rotates the image 180 degrees clockwise.
+    ///
+    /// This method rotates the image 180 degrees clockwise by performing two consecutive 90-degree clockwise rotations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.rotate_180(); // Rotate the image 180 degrees clockwise
+    /// ```
+    #[inline]
+    pub fn rotate_180(&mut self) {
+        self.rotate_90();
+        self.rotate_90();
+    }
+
+    /// Rotates the image 270 degrees clockwise.
+    ///
+    /// This method rotates the image 270 degrees clockwise by performing a diagonal flip followed by a vertical flip.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.rotate_270(); // Rotate the image 270 degrees clockwise
+    ///
+    /// assert_eq!(image.width(), 600);
+    /// ```
+    #[inline]
+    pub fn rotate_270(&mut self) {
+        self.flip_diagonally();
+        self.flip_vertically();
+    }
+
+    /// Rotates the image clockwise by an arbitrary angle.
+    ///
+    /// Multiples of 90 degrees are rotated losslessly. Other angles expand the canvas to fit
+    /// the whole rotated image, sample the source with bilinear interpolation and fill
+    /// uncovered areas with `background`. The image is left unchanged when the angle isn't a
+    /// finite number.
+    ///
+    /// # Parameters
+    ///
+    /// - `degrees`: The clockwise rotation angle in degrees.
+    /// - `background`: The color used for areas not covered by the rotated image.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 255); 100 * 100], 100, 100);
+    /// image.rotate(45.0, RGBA8::new(255, 255, 255, 255));
+    ///
+    /// assert_eq!(image.width(), 141);
+    /// assert_eq!(image.height(), 141);
+    /// ```
+    pub fn rotate(&mut self, degrees: f32, background: RGBA8) {
+        if !degrees.is_finite() {
+            return;
+        }
+
+        let degrees = degrees.rem_euclid(360.0);
+
+        if degrees == 90.0 {
+            self.rotate_90();
+        } else if degrees == 180.0 {
+            self.rotate_180();
+        } else if degrees == 270.0 {
+            self.rotate_270();
+        } else if degrees != 0.0 {
+            self.rotate_bilinear(degrees.to_radians(), background);
+        }
+    }
+
+    fn rotate_bilinear(&mut self, radians: f32, background: RGBA8) {
+        let (sin, cos) = (radians.sin() as f64, radians.cos() as f64);
+        let (src_width, src_height) = (self.width as f64, self.height as f64);
+
+        let width = (src_width * cos.abs() + src_height * sin.abs()).round() as usize;
+        let height = (src_width * sin.abs() + src_height * cos.abs()).round() as usize;
+
+        let (src_cx, src_cy) = (src_width / 2.0, src_height / 2.0);
+        let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+
+        let mut buf = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let dx = x as f64 + 0.5 - cx;
+                let dy = y as f64 + 0.5 - cy;
+
+                // inverse rotation maps destination pixel centers back to the source
+                let sx = dx * cos + dy * sin + src_cx - 0.5;
+                let sy = -dx * sin + dy * cos + src_cy - 0.5;
+
+                buf.push(self.sample_bilinear(sx, sy).unwrap_or(background));
+            }
+        }
+
+        self.data = buf;
+        self.width = width;
+        self.height = height;
+    }
+
+    fn sample_bilinear(&self, x: f64, y: f64) -> Option<RGBA8> {
+        if x <= -1.0 || y <= -1.0 || x >= self.width as f64 || y >= self.height as f64 {
+            return None;
+        }
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        // accumulate premultiplied values, so transparent pixels don't bleed their color
+        let mut acc = [0.0f64; 4];
+        let mut coverage = 0.0;
+
+        for (px, py, weight) in [
+            (x0, y0, (1.0 - fx) * (1.0 - fy)),
+            (x0 + 1.0, y0, fx * (1.0 - fy)),
+            (x0, y0 + 1.0, (1.0 - fx) * fy),
+            (x0 + 1.0, y0 + 1.0, fx * fy),
+        ] {
+            if px < 0.0 || py < 0.0 || px >= self.width as f64 || py >= self.height as f64 {
+                continue;
+            }
+
+            let pixel = self.data[py as usize * self.width + px as usize];
+            let alpha = pixel.a as f64 * weight;
+
+            acc[0] += pixel.r as f64 * alpha;
+            acc[1] += pixel.g as f64 * alpha;
+            acc[2] += pixel.b as f64 * alpha;
+            acc[3] += alpha;
+            coverage += weight;
+        }
+
+        if coverage < 0.5 {
+            return None;
+        }
+
+        if acc[3] == 0.0 {
+            return Some(RGBA8::new(0, 0, 0, 0));
+        }
+
+        Some(RGBA8::new(
+            (acc[0] / acc[3]).round() as u8,
+            (acc[1] / acc[3]).round() as u8,
+            (acc[2] / acc[3]).round() as u8,
+            (acc[3] / coverage).round() as u8,
+        ))
+    }
+
+    /// Crops the image to the specified rectangle.
+    ///
+    /// # Parameters
+    ///
+    /// - `x`: The left edge of the crop area.
+    /// - `y`: The top edge of the crop area.
+    /// - `width`: The width of the crop area.
+    /// - `height`: The height of the crop area.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OperationError`] if the crop area is empty or does not fit inside the image.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.crop(100, 100, 400, 300)?;
+    ///
+    /// assert_eq!(image.width(), 400);
+    /// assert_eq!(image.height(), 300);
+    /// # Ok::<(), rimage::error::OperationError>(())
+    /// ```
+    pub fn crop(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<(), OperationError> {
+        if width == 0
+            || height == 0
+            || x.saturating_add(width) > self.width
+            || y.saturating_add(height) > self.height
+            || self.width == 0
+        {
+            return Err(OperationError::CropOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+
+        self.data = self
+            .data
+            .chunks_exact(self.width)
+            .skip(y)
+            .take(height)
+            .flat_map(|row| &row[x..x + width])
+            .copied()
+            .collect();
+        self.width = width;
+        self.height = height;
+
+        Ok(())
+    }
+
+    /// Adds borders around the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `top`: The size of the top border.
+    /// - `right`: The size of the right border.
+    /// - `bottom`: The size of the bottom border.
+    /// - `left`: The size of the left border.
+    /// - `color`: The color of the added borders.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OperationError`] if the padded image size overflows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.pad(10, 20, 10, 20, RGBA8::new(255, 255, 255, 255))?;
+    ///
+    /// assert_eq!(image.width(), 840);
+    /// assert_eq!(image.height(), 620);
+    /// # Ok::<(), rimage::error::OperationError>(())
+    /// ```
+    pub fn pad(
+        &mut self,
+        top: usize,
+        right: usize,
+        bottom: usize,
+        left: usize,
+        color: RGBA8,
+    ) -> Result<(), OperationError> {
+        let size = left
+            .checked_add(self.width)
+            .and_then(|width| width.checked_add(right))
+            .zip(
+                top.checked_add(self.height)
+                    .and_then(|height| height.checked_add(bottom)),
+            )
+            .filter(|(width, height)| width.checked_mul(*height).is_some());
+
+        let Some((width, height)) = size else {
+            return Err(OperationError::PaddingTooLarge {
+                top,
+                right,
+                bottom,
+                left,
+            });
+        };
+
+        let mut buf = Vec::with_capacity(width * height);
+
+        buf.resize(top * width, color);
+
+        // rows are sliced by index, as images without width have no chunks to iterate
+        for y in 0..self.height {
+            buf.resize(buf.len() + left, color);
+            buf.extend_from_slice(&self.data[y * self.width..(y + 1) * self.width]);
+            buf.resize(buf.len() + right, color);
+        }
+
+        buf.resize(width * height, color);
+
+        self.data = buf;
+        self.width = width;
+        self.height = height;
+
+        Ok(())
+    }
+
+    /// Trims uniform borders from the image.
+    ///
+    /// The color of the top-left pixel is used as the border color. Rows and columns are removed
+    /// from every side while all of their pixels differ from the border color by at most
+    /// `tolerance` in every channel. Images that consist only of the border color are left unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// - `tolerance`: The maximum per-channel difference to the border color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 255); 10 * 10], 10, 10);
+    /// image.pad(5, 5, 5, 5, RGBA8::new(255, 255, 255, 255))?;
+    /// image.trim(0);
+    ///
+    /// assert_eq!(image.width(), 10);
+    /// assert_eq!(image.height(), 10);
+    /// # Ok::<(), rimage::error::OperationError>(())
+    /// ```
+    pub fn trim(&mut self, tolerance: u8) {
+        // empty images have no border to trim
+        let Some(&border) = self
+            .data
+            .first()
+            .filter(|_| self.width > 0 && self.height > 0)
+        else {
+            return;
+        };
+
+        let is_border = |pixel: &RGBA8| {
+            pixel.r.abs_diff(border.r) <= tolerance
+                && pixel.g.abs_diff(border.g) <= tolerance
+                && pixel.b.abs_diff(border.b) <= tolerance
+                && pixel.a.abs_diff(border.a) <= tolerance
+        };
+
+        let row_is_border = |y: usize| {
+            self.data[y * self.width..(y + 1) * self.width]
+                .iter()
+                .all(is_border)
+        };
+
+        let Some(top) = (0..self.height).find(|&y| !row_is_border(y)) else {
+            return;
+        };
+        let bottom = (0..self.height)
+            .rfind(|&y| !row_is_border(y))
+            .unwrap_or(top);
+
+        let column_is_border =
+            |x: usize| (top..=bottom).all(|y| is_border(&self.data[y * self.width + x]));
+
+        let left = (0..self.width).find(|&x| !column_is_border(x)).unwrap_or(0);
+        let right = (0..self.width)
+            .rfind(|&x| !column_is_border(x))
+            .unwrap_or(self.width - 1);
+
+        self.crop(left, top, right - left + 1, bottom - top + 1)
+            .expect("trim area is always inside of the image");
+    }
+
+    /// Converts the image to grayscale.
+    ///
+    /// Luma is computed with Rec. 709 coefficients, alpha is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(255, 0, 0, 255); 800 * 600], 800, 600);
+    /// image.grayscale();
+    ///
+    /// assert_eq!(image.data()[0], RGBA8::new(54, 54, 54, 255));
+    /// ```
+    pub fn grayscale(&mut self) {
+        for pixel in self.data.iter_mut() {
+            let luma =
+                (pixel.r as u32 * 2126 + pixel.g as u32 * 7152 + pixel.b as u32 * 722 + 5000)
+                    / 10000;
+            let luma = luma as u8;
+
+            (pixel.r, pixel.g, pixel.b) = (luma, luma, luma);
+        }
+    }
+
+    /// Composites the image onto a background, making it fully opaque.
+    ///
+    /// # Parameters
+    ///
+    /// - `background`: The [`Background`] placed behind transparent pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::{RGB8, RGBA8};
+    /// use rimage::{config::Background, Image};
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(255, 0, 0, 0); 800 * 600], 800, 600);
+    /// image.flatten(&Background::Color(RGB8::new(0, 0, 255)));
+    ///
+    /// assert_eq!(image.data()[0], RGBA8::new(0, 0, 255, 255));
+    /// ```
+    pub fn flatten(&mut self, background: &Background) {
+        let blend = |fg: u8, bg: u8, alpha: u8| {
+            ((fg as u32 * alpha as u32 + bg as u32 * (255 - alpha as u32) + 127) / 255) as u8
+        };
+
+        for (i, pixel) in self.data.iter_mut().enumerate() {
+            let bg = background.color_at(i % self.width, i / self.width);
+
+            *pixel = RGBA8::new(
+                blend(pixel.r, bg.r, pixel.a),
+                blend(pixel.g, bg.g, pixel.a),
+                blend(pixel.b, bg.b, pixel.a),
+                255,
+            );
+        }
+    }
+
+    /// Adjusts the brightness of the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: The amount added to each color channel, values are clamped to the 0-255 range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(100, 100, 100, 255); 800 * 600], 800, 600);
+    /// image.brightness(-20);
+    ///
+    /// assert_eq!(image.data()[0], RGBA8::new(80, 80, 80, 255));
+    /// ```
+    pub fn brightness(&mut self, value: i16) {
+        self.map_channels(|c| (c as i32 + value as i32).clamp(0, 255) as u8);
+    }
+
+    /// Adjusts the contrast of the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: The contrast adjustment in percent, negative values reduce and positive values
+    ///   increase the contrast. Values are clamped to the -100-100 range, -100 turns the image
+    ///   uniformly gray and NaN leaves it unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(200, 200, 200, 255); 800 * 600], 800, 600);
+    /// image.contrast(-100.0);
+    ///
+    /// assert_eq!(image.data()[0], RGBA8::new(128, 128, 128, 255));
+    /// ```
+    pub fn contrast(&mut self, value: f32) {
+        if value.is_nan() {
+            return;
+        }
+
+        let factor = ((100.0 + value.clamp(-100.0, 100.0)) / 100.0).powi(2);
+
+        self.map_channels(|c| {
+            let c = (c as f32 / 255.0 - 0.5) * factor + 0.5;
+
+            (c * 255.0).round().clamp(0.0, 255.0) as u8
+        });
+    }
+
+    /// Applies gamma correction to the image.
+    ///
+    /// # Parameters
+    ///
+    /// - `value`: The gamma value, values above 1.0 brighten and values below 1.0 darken the image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OperationError`] if the gamma value is not a positive number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let mut image = Image::new(vec![RGBA8::new(64, 64, 64, 255); 800 * 600], 800, 600);
+    /// image.gamma(2.0)?;
+    ///
+    /// assert_eq!(image.data()[0], RGBA8::new(128, 128, 128, 255));
+    /// # Ok::<(), rimage::error::OperationError>(())
+    /// ```
+    pub fn gamma(&mut self, value: f32) -> Result<(), OperationError> {
+        if !(value.is_finite() && value > 0.0) {
+            return Err(OperationError::InvalidGamma(value));
+        }
+
+        self.map_channels(|c| ((c as f32 / 255.0).powf(1.0 / value) * 255.0).round() as u8);
+
+        Ok(())
+    }
+
+    /// Maps every color channel through a lookup table built from `f`, alpha is left untouched.
+    fn map_channels(&mut self, f: impl Fn(u8) -> u8) {
+        let lut: Vec<u8> = (0..=255).map(f).collect();
+
+        for pixel in self.data.iter_mut() {
+            pixel.r = lut[pixel.r as usize];
+            pixel.g = lut[pixel.g as usize];
+            pixel.b = lut[pixel.b as usize];
+        }
+    }
+
+    /// Gets a reference to the pixel data of the image.
+    ///
+    /// # Returns
+    ///
+    /// Returns a reference to the RGBA8 pixel data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let image_data = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    /// let image = Image::new(image_data, 800, 600);
+    /// let data_reference = image.data();
+    /// ```
+    #[inline]
+    pub fn data(&self) -> &[RGBA8] {
+        &self.data
+    }
+
+    /// Gets the width of the image in pixels.
+    ///
+    /// # Returns
+    ///
+    /// Returns the width of the image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let image_data = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    /// let image = Image::new(image_data, 800, 600);
+    /// let width = image.width();
+    ///
+    /// assert_eq!(width, 800)
+    /// ```
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the height of the image in pixels.
+    ///
+    /// # Returns
+    ///
+    /// Returns the height of the image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rimage::rgb::RGBA8;
+    /// use rimage::Image;
+    ///
+    /// let image_data = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    /// let image = Image::new(image_data, 800, 600);
+    /// let height = image.height();
+    ///
+    /// assert_eq!(height, 600)
+    /// ```
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+}
+
+/// Converts the image to RGBA8, dropping any extra bit depth.
+impl From<DynamicImage> for Image {
+    fn from(image: DynamicImage) -> Self {
+        let image = image.into_rgba8();
+
+        Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            data: image.as_raw().as_rgba().to_vec(),
+        }
+    }
+}
+
+impl From<Image> for DynamicImage {
+    fn from(image: Image) -> Self {
+        let data = image
+            .data
+            .into_iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a])
+            .collect();
+
+        DynamicImage::ImageRgba8(
+            RgbaImage::from_raw(image.width as u32, image.height as u32, data)
+                .expect("image data always matches its dimensions"),
+        )
+    }
+}
+
+#[cfg(feature = "quantization")]
+pub(crate) mod dithering;
+pub(crate) mod tone_mapping;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,511 @@
+use super::*;
+
+#[test]
+fn new_image() {
+    let pixel_data: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); 100 * 50];
+    let image = Image::new(pixel_data.clone(), 100, 50);
+
+    assert_eq!(image.data(), &pixel_data);
+    assert_eq!(image.width(), 100);
+    assert_eq!(image.height(), 50);
+}
+
+#[test]
+#[cfg(feature = "resizing")]
+fn resize_image_smaller() {
+    let image_data: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); 100 * 50];
+    let mut image = Image::new(image_data, 100, 50);
+
+    let resize_config = ResizeConfig::default().with_width(50);
+    image.resize(&resize_config).unwrap();
+
+    assert_eq!(image.data(), &[RGBA8::new(0, 0, 0, 0); 50 * 25]);
+    assert_eq!(image.width(), 50);
+    assert_eq!(image.height(), 25);
+}
+
+#[test]
+#[cfg(feature = "resizing")]
+fn resize_image_bigger() {
+    let image_data: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); 100 * 50];
+    let mut image = Image::new(image_data, 100, 50);
+
+    let resize_config = ResizeConfig::default().with_width(200);
+    image.resize(&resize_config).unwrap();
+
+    assert_eq!(image.data(), &vec![RGBA8::new(0, 0, 0, 0); 200 * 100]);
+    assert_eq!(image.width(), 200);
+    assert_eq!(image.height(), 100);
+}
+
+#[test]
+#[cfg(feature = "resizing")]
+fn resize_image_width_and_height() {
+    let image_data: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); 100 * 50];
+    let mut image = Image::new(image_data, 100, 50);
+
+    let resize_config = ResizeConfig::default().with_width(200).with_height(150);
+    image.resize(&resize_config).unwrap();
+
+    assert_eq!(image.data(), &[RGBA8::new(0, 0, 0, 0); 200 * 150]);
+    assert_eq!(image.width(), 200);
+    assert_eq!(image.height(), 150);
+}
+
+#[test]
+#[cfg(feature = "quantization")]
+fn quantize_image() {
+    let image_data: Vec<RGBA8> = vec![RGBA8::new(0, 0, 0, 0); 800 * 600];
+    let mut image = Image::new(image_data.clone(), 800, 600);
+
+    let quantization_config = QuantizationConfig::default();
+    assert!(image.quantize(&quantization_config).is_ok());
+
+    // Test quantization with quality
+    let quantization_config = QuantizationConfig::default().with_quality(50).unwrap();
+    assert!(image.quantize(&quantization_config).is_ok());
+}
+
+#[test]
+fn flip_diagonally() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+    ];
+
+    #[rustfmt::skip]
+    let test_image_data = vec![
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+    ];
+
+    let mut image = Image::new(image_data.clone(), 5, 3);
+
+    image.flip_diagonally();
+
+    assert_ne!(image.data, image_data);
+
+    assert_eq!(image.width, 3);
+    assert_eq!(image.height, 5);
+
+    assert_eq!(image.data, test_image_data);
+}
+
+#[test]
+fn flip_horizontally() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+    ];
+
+    #[rustfmt::skip]
+    let test_image_data = vec![
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+    ];
+
+    let mut image = Image::new(image_data.clone(), 3, 5);
+
+    image.flip_horizontally();
+
+    assert_ne!(image.data, image_data);
+
+    assert_eq!(image.width, 3);
+    assert_eq!(image.height, 5);
+
+    assert_eq!(image.data, test_image_data);
+}
+
+#[test]
+fn rotate_180() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+    ];
+
+    #[rustfmt::skip]
+    let test_image_data = vec![
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+        RGBA8::new(0, 0, 0, 255), RGBA8::new(0, 0, 0, 0), RGBA8::new(0, 0, 0, 0),
+    ];
+
+    let mut image = Image::new(image_data.clone(), 3, 5);
+
+    image.rotate_180();
+
+    assert_ne!(image.data, image_data);
+
+    assert_eq!(image.width, 3);
+    assert_eq!(image.height, 5);
+
+    assert_eq!(image.data, test_image_data);
+}
+
+#[test]
+fn rotate_90_and_270() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(1, 0, 0, 255), RGBA8::new(2, 0, 0, 255), RGBA8::new(3, 0, 0, 255),
+        RGBA8::new(4, 0, 0, 255), RGBA8::new(5, 0, 0, 255), RGBA8::new(6, 0, 0, 255),
+    ];
+
+    #[rustfmt::skip]
+    let rotated_90 = vec![
+        RGBA8::new(4, 0, 0, 255), RGBA8::new(1, 0, 0, 255),
+        RGBA8::new(5, 0, 0, 255), RGBA8::new(2, 0, 0, 255),
+        RGBA8::new(6, 0, 0, 255), RGBA8::new(3, 0, 0, 255),
+    ];
+
+    #[rustfmt::skip]
+    let rotated_270 = vec![
+        RGBA8::new(3, 0, 0, 255), RGBA8::new(6, 0, 0, 255),
+        RGBA8::new(2, 0, 0, 255), RGBA8::new(5, 0, 0, 255),
+        RGBA8::new(1, 0, 0, 255), RGBA8::new(4, 0, 0, 255),
+    ];
+
+    let mut image = Image::new(image_data.clone(), 3, 2);
+    image.rotate_90();
+
+    assert_eq!(image.width, 2);
+    assert_eq!(image.height, 3);
+    assert_eq!(image.data, rotated_90);
+
+    let mut image = Image::new(image_data.clone(), 3, 2);
+    image.rotate_270();
+
+    assert_eq!(image.width, 2);
+    assert_eq!(image.height, 3);
+    assert_eq!(image.data, rotated_270);
+
+    let mut image = Image::new(image_data, 3, 2);
+    image.rotate(-90.0, RGBA8::new(0, 0, 0, 0));
+
+    assert_eq!(image.data, rotated_270);
+}
+
+#[test]
+fn rotate_arbitrary_angle() {
+    let mut image = Image::new(vec![RGBA8::new(255, 0, 0, 255); 100 * 50], 100, 50);
+
+    image.rotate(30.0, RGBA8::new(0, 0, 255, 255));
+
+    // 100 * cos(30) + 50 * sin(30) and 100 * sin(30) + 50 * cos(30)
+    assert_eq!(image.width, 112);
+    assert_eq!(image.height, 93);
+    assert_eq!(image.data.len(), 112 * 93);
+
+    // corners are filled with background, center keeps the image color
+    assert_eq!(image.data[0], RGBA8::new(0, 0, 255, 255));
+    assert_eq!(image.data[112 * 93 - 1], RGBA8::new(0, 0, 255, 255));
+    assert_eq!(image.data[46 * 112 + 56], RGBA8::new(255, 0, 0, 255));
+}
+
+#[test]
+fn rotate_non_finite_angle() {
+    let mut image = Image::new(vec![RGBA8::new(255, 0, 0, 255); 100 * 50], 100, 50);
+
+    for degrees in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        assert!(image.apply(&Operation::Rotate(degrees)).is_err());
+
+        image.rotate(degrees, RGBA8::new(0, 0, 0, 0));
+        assert_eq!((image.width, image.height), (100, 50));
+    }
+}
+
+#[test]
+fn flip_vertically() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(1, 0, 0, 255), RGBA8::new(2, 0, 0, 255),
+        RGBA8::new(3, 0, 0, 255), RGBA8::new(4, 0, 0, 255),
+        RGBA8::new(5, 0, 0, 255), RGBA8::new(6, 0, 0, 255),
+    ];
+
+    #[rustfmt::skip]
+    let test_image_data = vec![
+        RGBA8::new(5, 0, 0, 255), RGBA8::new(6, 0, 0, 255),
+        RGBA8::new(3, 0, 0, 255), RGBA8::new(4, 0, 0, 255),
+        RGBA8::new(1, 0, 0, 255), RGBA8::new(2, 0, 0, 255),
+    ];
+
+    let mut image = Image::new(image_data, 2, 3);
+
+    image.flip_vertically();
+
+    assert_eq!(image.width, 2);
+    assert_eq!(image.height, 3);
+    assert_eq!(image.data, test_image_data);
+}
+
+#[test]
+fn crop() {
+    let image_data: Vec<RGBA8> = (0..25).map(|i| RGBA8::new(i, 0, 0, 255)).collect();
+    let mut image = Image::new(image_data, 5, 5);
+
+    image.crop(1, 2, 3, 2).unwrap();
+
+    assert_eq!(image.width, 3);
+    assert_eq!(image.height, 2);
+    assert_eq!(
+        image.data.iter().map(|p| p.r).collect::<Vec<_>>(),
+        vec![11, 12, 13, 16, 17, 18]
+    );
+}
+
+#[test]
+fn crop_out_of_bounds() {
+    let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 5 * 5], 5, 5);
+
+    assert_eq!(
+        image.crop(3, 0, 3, 5),
+        Err(OperationError::CropOutOfBounds {
+            x: 3,
+            y: 0,
+            width: 3,
+            height: 5
+        })
+    );
+    assert!(image.crop(0, 0, 0, 5).is_err());
+    assert!(image.crop(0, usize::MAX, 5, 5).is_err());
+
+    assert_eq!(image.width, 5);
+    assert_eq!(image.height, 5);
+}
+
+#[test]
+fn pad() {
+    let mut image = Image::new(vec![RGBA8::new(255, 255, 255, 255); 2 * 2], 2, 2);
+
+    image.pad(1, 2, 3, 4, RGBA8::new(0, 0, 0, 0)).unwrap();
+
+    assert_eq!(image.width, 8);
+    assert_eq!(image.height, 6);
+    assert_eq!(image.data.len(), 8 * 6);
+
+    let opaque: Vec<usize> = image
+        .data
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.a == 255)
+        .map(|(i, _)| i)
+        .collect();
+
+    assert_eq!(opaque, vec![8 + 4, 8 + 5, 16 + 4, 16 + 5]);
+}
+
+#[test]
+fn pad_overflow() {
+    let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 2 * 2], 2, 2);
+
+    assert!(image.pad(0, usize::MAX, 0, 0, RGBA8::default()).is_err());
+    assert!(image
+        .pad(usize::MAX / 4, 0, 0, usize::MAX / 4, RGBA8::default())
+        .is_err());
+
+    assert_eq!(image.width, 2);
+    assert_eq!(image.height, 2);
+}
+
+#[test]
+fn empty_images() {
+    let mut image = Image::new(vec![], 0, 3);
+
+    image.trim(0);
+    assert_eq!((image.width, image.height), (0, 3));
+
+    assert!(image.crop(0, 0, 1, 1).is_err());
+
+    image.pad(1, 2, 1, 2, RGBA8::new(255, 0, 0, 255)).unwrap();
+    assert_eq!((image.width, image.height), (4, 5));
+    assert_eq!(image.data, vec![RGBA8::new(255, 0, 0, 255); 4 * 5]);
+
+    let mut image = Image::new(vec![], 3, 0);
+    image.trim(0);
+    image.pad(1, 0, 0, 0, RGBA8::default()).unwrap();
+    assert_eq!((image.width, image.height), (3, 1));
+}
+
+#[test]
+fn trim() {
+    let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 255); 3 * 2], 3, 2);
+    image
+        .pad(4, 1, 2, 3, RGBA8::new(250, 250, 250, 255))
+        .unwrap();
+
+    // slightly different border pixel is accepted with tolerance
+    image.data[0] = RGBA8::new(255, 255, 255, 255);
+
+    let mut strict = image.clone();
+    strict.trim(0);
+
+    assert_eq!(strict.width, 7);
+    assert_eq!(strict.height, 8);
+
+    image.trim(5);
+
+    assert_eq!(image.width, 3);
+    assert_eq!(image.height, 2);
+    assert!(image.data.iter().all(|p| *p == RGBA8::new(0, 0, 0, 255)));
+}
+
+#[test]
+fn trim_uniform_image() {
+    let mut image = Image::new(vec![RGBA8::new(10, 20, 30, 255); 4 * 4], 4, 4);
+
+    image.trim(0);
+
+    assert_eq!(image.width, 4);
+    assert_eq!(image.height, 4);
+}
+
+#[test]
+fn color_adjustments() {
+    let mut image = Image::new(vec![RGBA8::new(0, 128, 255, 100); 2], 2, 1);
+    image.grayscale();
+    assert_eq!(image.data[0], RGBA8::new(110, 110, 110, 100));
+
+    let mut image = Image::new(vec![RGBA8::new(0, 128, 250, 100); 2], 2, 1);
+    image.brightness(10);
+    assert_eq!(image.data[0], RGBA8::new(10, 138, 255, 100));
+    image.brightness(-20);
+    assert_eq!(image.data[0], RGBA8::new(0, 118, 235, 100));
+    image.brightness(i16::MAX);
+    assert_eq!(image.data[0], RGBA8::new(255, 255, 255, 100));
+    image.brightness(i16::MIN);
+    assert_eq!(image.data[0], RGBA8::new(0, 0, 0, 100));
+
+    let mut image = Image::new(vec![RGBA8::new(64, 128, 192, 100); 2], 2, 1);
+    image.contrast(100.0);
+    assert_eq!(image.data[0], RGBA8::new(0, 130, 255, 100));
+
+    let mut image = Image::new(vec![RGBA8::new(64, 128, 192, 100); 2], 2, 1);
+    image.contrast(-500.0);
+    assert_eq!(image.data[0], RGBA8::new(128, 128, 128, 100));
+    image.contrast(f32::NAN);
+    assert_eq!(image.data[0], RGBA8::new(128, 128, 128, 100));
+
+    let mut image = Image::new(vec![RGBA8::new(0, 64, 255, 100); 2], 2, 1);
+    image.gamma(0.5).unwrap();
+    assert_eq!(image.data[0], RGBA8::new(0, 16, 255, 100));
+
+    assert_eq!(image.gamma(0.0), Err(OperationError::InvalidGamma(0.0)));
+    assert!(image.gamma(f32::NAN).is_err());
+}
+
+#[test]
+fn flatten() {
+    #[rustfmt::skip]
+    let image_data = vec![
+        RGBA8::new(255, 0, 0, 255), RGBA8::new(255, 0, 0, 0),
+        RGBA8::new(255, 0, 0, 128), RGBA8::new(0, 0, 0, 51),
+    ];
+
+    let mut image = Image::new(image_data.clone(), 2, 2);
+    image.flatten(&Background::Color(rgb::RGB8::new(0, 0, 255)));
+
+    assert_eq!(
+        image.data,
+        vec![
+            RGBA8::new(255, 0, 0, 255),
+            RGBA8::new(0, 0, 255, 255),
+            RGBA8::new(128, 0, 127, 255),
+            RGBA8::new(0, 0, 204, 255),
+        ]
+    );
+
+    let mut image = Image::new(vec![RGBA8::new(0, 0, 0, 0); 4], 4, 1);
+    image.flatten(&Background::Checkerboard(2));
+
+    assert_eq!(
+        image.data,
+        vec![
+            RGBA8::new(255, 255, 255, 255),
+            RGBA8::new(255, 255, 255, 255),
+            RGBA8::new(204, 204, 204, 255),
+            RGBA8::new(204, 204, 204, 255),
+        ]
+    );
+}
+
+#[test]
+fn apply_operations() {
+    let mut image = Image::new(vec![RGBA8::new(255, 0, 0, 255); 10 * 20], 10, 20);
+
+    for operation in [
+        Operation::Crop {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+        },
+        Operation::Pad {
+            top: 0,
+            right: 5,
+            bottom: 0,
+            left: 0,
+            color: RGBA8::new(0, 0, 0, 0),
+        },
+        Operation::Rotate(90.0),
+        Operation::FlipHorizontally,
+        Operation::FlipVertically,
+        Operation::Trim(0),
+        Operation::Grayscale,
+        Operation::Brightness(0),
+        Operation::Contrast(0.0),
+        Operation::Gamma(1.0),
+    ] {
+        image.apply(&operation).unwrap();
+    }
+
+    assert_eq!(image.width, 10);
+    assert_eq!(image.height, 10);
+    assert!(image.data.iter().all(|p| *p == RGBA8::new(54, 54, 54, 255)));
+
+    assert!(image
+        .apply(&Operation::Crop {
+            x: 5,
+            y: 5,
+            width: 10,
+            height: 10
+        })
+        .is_err());
+}
+
+#[test]
+fn dynamic_image_conversion() {
+    let dynamic = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(4, 3, image::Rgb([1, 2, 3])));
+
+    let image = Image::from(dynamic);
+
+    assert_eq!(image.width(), 4);
+    assert_eq!(image.height(), 3);
+    assert!(image.data().iter().all(|p| *p == RGBA8::new(1, 2, 3, 255)));
+
+    let dynamic = DynamicImage::from(image);
+
+    assert_eq!(dynamic.width(), 4);
+    assert_eq!(dynamic.height(), 3);
+    assert_eq!(dynamic.color(), image::ColorType::Rgba8);
+    assert_eq!(&dynamic.as_bytes()[..4], &[1, 2, 3, 255]);
+}
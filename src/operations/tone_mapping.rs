@@ -0,0 +1,399 @@
+//! Tone mapping of floating point HDR images onto the SDR range.
+
+use image::{DynamicImage, ImageBuffer, Rgb, Rgba};
+
+use crate::config::{ToneMapping, ToneMappingConfig};
+
+/// Luminance of the SDR reference white in nits, as defined by ITU-R BT.2408.
+const REFERENCE_WHITE: f32 = 203.0;
+
+/// Transfer function of HDR signals from ITU-R BT.2100.
+#[cfg(any(feature = "heif", feature = "jxl", test))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Transfer {
+    /// Perceptual quantizer from SMPTE ST 2084, absolute up to 10000 nits.
+    Pq,
+
+    /// Hybrid log-gamma, relative to the display peak.
+    Hlg,
+}
+
+/// Nominal peak luminance of HLG displays in nits, which sets the HLG system gamma to 1.2.
+#[cfg(any(feature = "heif", feature = "jxl", test))]
+const HLG_PEAK: f32 = 1000.0;
+
+/// Decodes an image with PQ or HLG signals to linear floating point sRGB, where 1.0 is the
+/// reference white, so it's tone mapped like other HDR images.
+///
+/// Signals are taken in BT.2020 primaries as defined by BT.2100.
+#[cfg(any(feature = "heif", feature = "jxl", test))]
+pub(crate) fn linearize(image: DynamicImage, transfer: Transfer) -> DynamicImage {
+    let linear = |rgb: &mut [f32]| {
+        let [r, g, b] = match transfer {
+            Transfer::Pq => [rgb[0], rgb[1], rgb[2]].map(|c| pq_decode(c) / REFERENCE_WHITE),
+            Transfer::Hlg => {
+                let scene = [rgb[0], rgb[1], rgb[2]].map(hlg_decode);
+                let luminance = 0.2627 * scene[0] + 0.6780 * scene[1] + 0.0593 * scene[2];
+                let gain = HLG_PEAK * luminance.max(0.0).powf(0.2) / REFERENCE_WHITE;
+
+                scene.map(|c| c * gain)
+            }
+        };
+
+        rgb[0] = 1.6605 * r - 0.5876 * g - 0.0728 * b;
+        rgb[1] = -0.1246 * r + 1.1329 * g - 0.0083 * b;
+        rgb[2] = -0.0182 * r - 0.1006 * g + 1.1187 * b;
+    };
+
+    match image.color().has_alpha() {
+        true => {
+            let mut image = image.into_rgba32f();
+            image.pixels_mut().for_each(|p| linear(&mut p.0));
+
+            DynamicImage::ImageRgba32F(image)
+        }
+        false => {
+            let mut image = image.into_rgb32f();
+            image.pixels_mut().for_each(|p| linear(&mut p.0));
+
+            DynamicImage::ImageRgb32F(image)
+        }
+    }
+}
+
+/// Tone maps floating point images to 8-bit sRGB, other images are returned unchanged.
+///
+/// Alpha is kept as is, only clamped to the 0.0..1.0 range.
+pub(crate) fn tone_map(image: DynamicImage, config: &ToneMappingConfig) -> DynamicImage {
+    let operator = Operator::new(config);
+
+    match image {
+        DynamicImage::ImageRgb32F(image) => {
+            let (width, height) = image.dimensions();
+            let pixels = image
+                .into_raw()
+                .into_iter()
+                .map(|c| operator.map(c))
+                .collect();
+
+            DynamicImage::ImageRgb8(
+                ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, pixels).unwrap(),
+            )
+        }
+        DynamicImage::ImageRgba32F(image) => {
+            let (width, height) = image.dimensions();
+            let pixels = image
+                .into_raw()
+                .chunks_exact(4)
+                .flat_map(|p| {
+                    [
+                        operator.map(p[0]),
+                        operator.map(p[1]),
+                        operator.map(p[2]),
+                        encode(p[3]),
+                    ]
+                })
+                .collect();
+
+            DynamicImage::ImageRgba8(
+                ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, pixels).unwrap(),
+            )
+        }
+        image => image,
+    }
+}
+
+/// Tone mapping operator with precomputed parameters.
+struct Operator {
+    operator: ToneMapping,
+
+    /// Linear scale from the exposure.
+    scale: f32,
+
+    /// Peak luminance relative to the reference white.
+    white: f32,
+
+    /// Peak luminance in nits.
+    peak: f32,
+}
+
+impl Operator {
+    fn new(config: &ToneMappingConfig) -> Self {
+        Self {
+            operator: config.operator(),
+            scale: config.exposure().exp2(),
+            white: config.peak_luminance() / REFERENCE_WHITE,
+            peak: config.peak_luminance(),
+        }
+    }
+
+    /// Maps a linear channel value to an 8-bit sRGB value.
+    fn map(&self, value: f32) -> u8 {
+        encode(oetf(self.apply(value)))
+    }
+
+    /// Maps a linear scene value, where 1.0 is the reference white, to a linear display
+    /// value in the 0.0..1.0 range.
+    fn apply(&self, value: f32) -> f32 {
+        // the limit keeps the curves from overflowing, it's far beyond any real peak
+        let x = if value.is_nan() {
+            0.0
+        } else {
+            (value * self.scale).clamp(0.0, 1e6)
+        };
+
+        let mapped = match self.operator {
+            ToneMapping::Clip => x,
+            ToneMapping::Reinhard => reinhard(x, self.white),
+            ToneMapping::Aces => aces(x),
+            ToneMapping::Hable => hable(x) / hable(self.white),
+            ToneMapping::Bt2390 => bt2390(x, self.peak),
+        };
+
+        mapped.clamp(0.0, 1.0)
+    }
+}
+
+/// Extended Reinhard operator, mapping `white` to 1.0.
+fn reinhard(x: f32, white: f32) -> f32 {
+    x * (1.0 + x / (white * white)) / (1.0 + x)
+}
+
+/// ACES filmic curve fit by Krzysztof Narkowicz.
+fn aces(x: f32) -> f32 {
+    let x = x * 0.6;
+
+    (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)
+}
+
+/// Filmic curve by John Hable, with the exposure bias of 2.0 from Uncharted 2.
+fn hable(x: f32) -> f32 {
+    const A: f32 = 0.15;
+    const B: f32 = 0.50;
+    const C: f32 = 0.10;
+    const D: f32 = 0.20;
+    const E: f32 = 0.02;
+    const F: f32 = 0.30;
+
+    let x = x * 2.0;
+
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+/// EETF from ITU-R BT.2390, compressing the source range up to `peak` nits into the SDR
+/// range with a Hermite spline in the PQ domain.
+fn bt2390(x: f32, peak: f32) -> f32 {
+    let source_max = pq_encode(peak);
+    let target_max = pq_encode(REFERENCE_WHITE);
+
+    let e1 = (pq_encode(x * REFERENCE_WHITE) / source_max).min(1.0);
+    let max_lum = target_max / source_max;
+    let ks = 1.5 * max_lum - 0.5;
+
+    let e2 = if e1 < ks || ks >= 1.0 {
+        e1.min(max_lum)
+    } else {
+        let t = (e1 - ks) / (1.0 - ks);
+        let (t2, t3) = (t * t, t * t * t);
+
+        (2.0 * t3 - 3.0 * t2 + 1.0) * ks
+            + (t3 - 2.0 * t2 + t) * (1.0 - ks)
+            + (-2.0 * t3 + 3.0 * t2) * max_lum
+    };
+
+    pq_decode(e2 * source_max) / REFERENCE_WHITE
+}
+
+const PQ_M1: f32 = 2610.0 / 16384.0;
+const PQ_M2: f32 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f32 = 3424.0 / 4096.0;
+const PQ_C2: f32 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+/// SMPTE ST 2084 inverse EOTF, from nits to a PQ signal.
+fn pq_encode(nits: f32) -> f32 {
+    let y = (nits / 10000.0).clamp(0.0, 1.0).powf(PQ_M1);
+
+    ((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y)).powf(PQ_M2)
+}
+
+/// SMPTE ST 2084 EOTF, from a PQ signal to nits.
+fn pq_decode(signal: f32) -> f32 {
+    let e = signal.clamp(0.0, 1.0).powf(1.0 / PQ_M2);
+
+    ((e - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * e)).powf(1.0 / PQ_M1) * 10000.0
+}
+
+/// HLG inverse OETF from ITU-R BT.2100, from a signal to linear scene light in 0.0..1.0.
+#[cfg(any(feature = "heif", feature = "jxl", test))]
+fn hlg_decode(signal: f32) -> f32 {
+    const A: f32 = 0.178_832_77;
+    const B: f32 = 0.284_668_92;
+    const C: f32 = 0.559_910_7;
+
+    let signal = signal.clamp(0.0, 1.0);
+
+    if signal <= 0.5 {
+        signal * signal / 3.0
+    } else {
+        (((signal - C) / A).exp() + B) / 12.0
+    }
+}
+
+/// sRGB transfer function.
+fn oetf(linear: f32) -> f32 {
+    if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[inline]
+fn encode(value: f32) -> u8 {
+    if value.is_nan() {
+        return 0;
+    }
+
+    (value.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb32FImage, RgbaImage};
+
+    use super::*;
+
+    const OPERATORS: [ToneMapping; 5] = [
+        ToneMapping::Clip,
+        ToneMapping::Reinhard,
+        ToneMapping::Aces,
+        ToneMapping::Hable,
+        ToneMapping::Bt2390,
+    ];
+
+    #[test]
+    fn operators_are_monotonic() {
+        for operator in OPERATORS {
+            let operator = Operator::new(&ToneMappingConfig::new(operator));
+
+            assert!(operator.apply(0.0) < 1e-6);
+
+            let mut previous = 0.0;
+            for i in 1..=1000 {
+                let value = operator.apply(i as f32 * 0.01);
+
+                assert!(value >= previous, "{:?} at {i}", operator.operator);
+                assert!(value <= 1.0);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn peak_maps_to_white() {
+        let config = ToneMappingConfig::default();
+
+        for operator in [
+            ToneMapping::Reinhard,
+            ToneMapping::Hable,
+            ToneMapping::Bt2390,
+        ] {
+            let operator = Operator::new(&ToneMappingConfig::new(operator));
+
+            assert!((operator.apply(config.peak_luminance() / REFERENCE_WHITE) - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn bt2390_keeps_shadows() {
+        let operator = Operator::new(&ToneMappingConfig::new(ToneMapping::Bt2390));
+
+        assert!((operator.apply(0.05) - 0.05).abs() < 1e-3);
+    }
+
+    #[test]
+    fn exposure() {
+        let config = ToneMappingConfig::new(ToneMapping::Clip)
+            .with_exposure(1.0)
+            .unwrap();
+
+        assert_eq!(Operator::new(&config).apply(0.25), 0.5);
+    }
+
+    #[test]
+    fn non_finite_values() {
+        let operator = Operator::new(&ToneMappingConfig::default());
+
+        assert_eq!(operator.map(f32::NAN), 0);
+        assert_eq!(operator.map(f32::NEG_INFINITY), 0);
+        assert_eq!(operator.map(f32::INFINITY), 255);
+    }
+
+    #[test]
+    fn tone_map_image() {
+        let image = Rgb32FImage::from_pixel(2, 2, Rgb([0.18, 1.0, 4.0]));
+        let config = ToneMappingConfig::new(ToneMapping::Reinhard);
+
+        let mapped = tone_map(DynamicImage::ImageRgb32F(image), &config).into_rgb8();
+        let pixel = mapped.get_pixel(0, 0).0;
+
+        assert!(pixel[0] < pixel[1] && pixel[1] < pixel[2]);
+        assert!(pixel[2] < 255);
+    }
+
+    #[test]
+    fn tone_map_pq_image() {
+        // reference white, 1000 nits and the PQ maximum of 10000 nits
+        let signals = [REFERENCE_WHITE, 1000.0, 10000.0]
+            .map(|nits| (pq_encode(nits) * u16::MAX as f32).round() as u16);
+        let image = ImageBuffer::<Rgba<u16>, _>::from_fn(3, 1, |x, _| {
+            let signal = signals[x as usize];
+
+            Rgba([signal, signal, signal, u16::MAX])
+        });
+
+        let linear = linearize(DynamicImage::ImageRgba16(image), Transfer::Pq).into_rgba32f();
+        let white = linear.get_pixel(0, 0).0;
+
+        assert!(
+            white[..3].iter().all(|c| (c - 1.0).abs() < 1e-2),
+            "{white:?}"
+        );
+        assert_eq!(white[3], 1.0);
+
+        let config = ToneMappingConfig::new(ToneMapping::Bt2390);
+        let mapped = tone_map(DynamicImage::ImageRgba32F(linear), &config).into_rgba8();
+        let [white, peak, max] = [0, 1, 2].map(|x| mapped.get_pixel(x, 0).0);
+
+        assert!(
+            white[0] < peak[0] && peak[0] <= max[0],
+            "{white:?} {peak:?} {max:?}"
+        );
+        assert!(white[0] < 255);
+        assert_eq!(white[3], 255);
+    }
+
+    #[test]
+    fn hlg_reference_white() {
+        // reference white is at 75% of the HLG signal
+        let image = Rgb32FImage::from_pixel(1, 1, Rgb([0.75, 0.75, 0.75]));
+        let linear = linearize(DynamicImage::ImageRgb32F(image), Transfer::Hlg).into_rgb32f();
+
+        assert!(linear
+            .get_pixel(0, 0)
+            .0
+            .iter()
+            .all(|c| (c - 1.0).abs() < 2e-2));
+    }
+
+    #[test]
+    fn keeps_sdr_images() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(2, 2));
+
+        assert_eq!(
+            tone_map(image.clone(), &ToneMappingConfig::default()),
+            image
+        );
+    }
+}